@@ -1,6 +1,9 @@
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
+use crate::notification::NotifierKind;
+use crate::pomodoro::{AutoStart, InterruptionKind, Interval};
+
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 pub struct Args {
@@ -24,10 +27,107 @@ pub struct Args {
     #[arg(short = 'n', long, default_value_t = 4)]
     pub pomodoros_until_long_break: usize,
     
+    /// Flowtime mode: work sessions count up until you move on, and earn a break in proportion to their length
+    #[arg(long, default_value_t = false)]
+    pub flowtime: bool,
+    
+    /// In flowtime mode, the share of a work session's length earned as a break, e.g. 0.2 for 1/5
+    #[arg(long, value_name = "FRACTION", default_value_t = 0.2, value_parser = parse_fraction)]
+    pub break_fraction: f64,
+    
+    /// Repeat a custom sequence of intervals instead of the pomodoro cycle, e.g. work:50,break:10,work:90:deep,long_break:30.
+    /// Each is KIND:MINUTES[:NAME]; a work interval's name is recorded as its session type
+    #[arg(long, value_name = "INTERVALS", value_delimiter = ',', value_parser = Interval::parse, conflicts_with = "flowtime")]
+    pub sequence: Vec<Interval>,
+    
+    /// Which intervals start by themselves when the one before runs out
+    #[arg(long, value_enum, default_value_t = AutoStart::Off)]
+    pub auto_start: AutoStart,
+    
+    /// Count down SECONDS before an interval starts by itself, so it can still be cancelled by pausing
+    #[arg(long, value_name = "SECONDS", default_value_t = 0)]
+    pub auto_start_grace: u64,
+    
+    /// Keep a work session running past zero, counting overtime, until you move on to the break
+    #[arg(long, default_value_t = false)]
+    pub overtime: bool,
+    
+    /// Remind you every MINUTES while in overtime; 0 turns the reminders off
+    #[arg(long, value_name = "MINUTES", default_value_t = 5)]
+    pub overtime_reminder: u64,
+    
+    /// Minutes added by `extend` (and the extend key) when no length is given
+    #[arg(long, value_name = "MINUTES", default_value_t = 5, value_parser = clap::value_parser!(u32).range(1..))]
+    pub extend_minutes: u32,
+    
+    /// Allow at most COUNT extensions per long-break cycle [default: no limit]
+    #[arg(long, value_name = "COUNT")]
+    pub max_extensions: Option<usize>,
+    
+    /// Strict mode: work sessions can't be paused or skipped, only abandoned by typing the confirmation phrase
+    #[arg(long, default_value_t = false)]
+    pub strict: bool,
+    
+    /// Phrase to type to abandon a work session in strict mode
+    #[arg(long, value_name = "TEXT", default_value = "I give up")]
+    pub strict_phrase: String,
+    
     /// Enable sound notifications
     #[arg(long, default_value_t = true)]
     pub sound: bool,
     
+    /// How to show notifications. osc9/osc777 send them through the terminal, which works over SSH
+    #[arg(long, value_enum, default_value_t = NotifierKind::Auto)]
+    pub notifier: NotifierKind,
+    
+    /// Start a background timer if none is running when a command needs one
+    #[arg(long, default_value_t = false)]
+    pub auto_spawn: bool,
+    
+    /// Label for what you are working on, published to integrations
+    #[arg(long)]
+    pub task: Option<String>,
+    
+    /// Key bindings file for the interactive view [default: ~/.config/pomodoro-cli/keys.conf, if it exists]
+    #[arg(long)]
+    pub keymap: Option<PathBuf>,
+    
+    /// Color theme: dark, light, high-contrast, monochrome, or a theme file [default: ~/.config/pomodoro-cli/theme.conf, if it exists]
+    #[arg(long, value_name = "NAME|PATH")]
+    pub theme: Option<String>,
+    
+    /// Use only ASCII characters, for terminals and screen readers that garble symbols and emoji
+    #[arg(long, default_value_t = false)]
+    pub ascii: bool,
+    
+    /// Pause breaks, and record work sessions as interrupted, when the terminal loses focus
+    /// for longer than SECONDS. Needs a terminal that reports focus changes.
+    #[arg(long, value_name = "SECONDS")]
+    pub auto_pause: Option<u64>,
+    
+    /// Don't capture the mouse in the interactive view, leaving clicks to the terminal (e.g. for selecting text)
+    #[arg(long, default_value_t = false)]
+    pub no_mouse: bool,
+    
+    /// Line-based interactive mode for screen readers: announces changes on new lines instead of redrawing, and reads typed commands
+    #[arg(long, default_value_t = false)]
+    pub plain: bool,
+    
+    /// MQTT broker host to publish timer state to (enables the MQTT integration)
+    #[cfg(feature = "mqtt")]
+    #[arg(long)]
+    pub mqtt_host: Option<String>,
+    
+    /// MQTT broker port
+    #[cfg(feature = "mqtt")]
+    #[arg(long, default_value_t = 1883)]
+    pub mqtt_port: u16,
+    
+    /// Prefix for the MQTT state and command topics
+    #[cfg(feature = "mqtt")]
+    #[arg(long, default_value = "pomodoro")]
+    pub mqtt_topic_prefix: String,
+    
     #[command(subcommand)]
     pub command: Option<Command>,
 }
@@ -43,6 +143,73 @@ pub enum Command {
     /// Skip to the next Pomodoro or break
     Next,
     
+    /// Show the state of the running timer
+    Status,
+    
+    /// Open the interactive view for a timer running in another process
+    Attach,
+    
+    /// Speak the JSON-RPC control protocol over stdin/stdout (see PROTOCOL.md), starting a background timer if needed
+    Rpc,
+    
+    /// Run the timer in the background without a UI, controlled through the other commands
+    Daemon,
+    
+    /// Manage a systemd user service that runs the background timer
+    Service {
+        #[command(subcommand)]
+        action: ServiceAction,
+    },
+    
+    /// Jot down a distraction without stopping the timer, or review the notes captured so far
+    Note {
+        /// Text of the note
+        #[arg(required_unless_present_any = ["list", "dismiss", "promote"])]
+        text: Vec<String>,
+        
+        /// List the notes that haven't been triaged yet
+        #[arg(long, conflicts_with_all = ["text", "dismiss", "promote"])]
+        list: bool,
+        
+        /// Dismiss the note with this id
+        #[arg(long, value_name = "ID", conflicts_with_all = ["text", "promote"])]
+        dismiss: Option<i64>,
+        
+        /// Make the note with this id the running timer's task
+        #[arg(long, value_name = "ID", conflicts_with = "text")]
+        promote: Option<i64>,
+    },
+    
+    /// Add time to the current interval, running or paused: keep working a little longer, or snooze a break
+    Extend {
+        /// Minutes to add [default: --extend-minutes]
+        #[arg(value_parser = clap::value_parser!(u32).range(1..))]
+        minutes: Option<u32>,
+    },
+    
+    /// Give up on the running work session, recording it as abandoned. In strict mode, give the confirmation phrase.
+    Abandon {
+        /// Confirmation phrase, required in strict mode
+        confirmation: Vec<String>,
+    },
+    
+    /// Log an interruption of the running work session: internal (your own urge) or external (someone else)
+    Interrupt {
+        /// Kind of interruption
+        #[arg(value_enum)]
+        kind: InterruptionKind,
+        
+        /// What interrupted you
+        reason: Vec<String>,
+    },
+    
+    /// Review sessions left open by a timer that stopped unexpectedly, and set how they ended
+    Repair {
+        /// Accept the suggested outcome for every session without asking
+        #[arg(short, long, default_value_t = false)]
+        yes: bool,
+    },
+    
     /// Show statistics of past Pomodoro sessions
     Stats {
         /// Number of sessions to show
@@ -53,7 +220,7 @@ pub enum Command {
         #[arg(short, long, default_value_t = 7)]
         days: i64,
         
-        /// Display type (sessions, daily, summary, types)
+        /// Display type (sessions, daily, summary, types, interruptions)
         #[arg(short = 't', long, default_value = "sessions")]
         display: String,
         
@@ -62,3 +229,32 @@ pub enum Command {
         chart: bool,
     },
 }
+
+#[derive(Subcommand, Debug, Clone, Copy)]
+pub enum ServiceAction {
+    /// Write the service and socket units and enable socket activation
+    Install,
+    
+    /// Disable and remove the units
+    Uninstall,
+    
+    /// Show the state of the units
+    Status,
+}
+
+/// A fraction above 0 and at most 1, given as a decimal or as a ratio such as `1/5`
+fn parse_fraction(text: &str) -> Result<f64, String> {
+    let value = match text.split_once('/') {
+        Some((numerator, denominator)) => {
+            let numerator: f64 = numerator.trim().parse().map_err(|_| format!("`{}` isn't a number", numerator.trim()))?;
+            let denominator: f64 = denominator.trim().parse().map_err(|_| format!("`{}` isn't a number", denominator.trim()))?;
+            numerator / denominator
+        },
+        None => text.trim().parse().map_err(|_| format!("`{}` isn't a number", text.trim()))?,
+    };
+    if value > 0.0 && value <= 1.0 {
+        Ok(value)
+    } else {
+        Err("must be above 0 and at most 1".to_string())
+    }
+}
@@ -0,0 +1,707 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use thiserror::Error;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+use crate::pomodoro::{InterruptionKind, Pomodoro, PomodoroCommand, PomodoroError, PomodoroStatus};
+
+/// Version of the JSON-RPC method set documented in PROTOCOL.md, reported by `initialize`.
+/// Bump it on any incompatible change.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+// Standard JSON-RPC 2.0 error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+// Application error: the timer rejected the command (e.g. starting a running timer)
+const TIMER_ERROR: i64 = -32000;
+
+#[derive(Error, Debug)]
+pub enum ControlError {
+    #[error("Control socket error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Invalid response from timer: {0}")]
+    Protocol(#[from] serde_json::Error),
+
+    #[error("Timer closed the connection")]
+    Disconnected,
+
+    #[error("{message}")]
+    Remote { code: i64, message: String },
+}
+
+impl ControlError {
+    /// The error a remote client gets when the timer rejects a command
+    pub fn rejected(error: PomodoroError) -> Self {
+        ControlError::Remote { code: TIMER_ERROR, message: error.to_string() }
+    }
+}
+
+#[derive(Debug)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+}
+
+/// Optional `project`/`task` parameters of `start` and `setTask`
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TaskParams {
+    project: Option<String>,
+    task: Option<String>,
+}
+
+fn task_params(params: Option<Value>) -> Result<TaskParams, RpcError> {
+    match params {
+        None | Some(Value::Null) => Ok(TaskParams::default()),
+        Some(value) => serde_json::from_value(value).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string())),
+    }
+}
+
+/// Parameters of `interrupt`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct InterruptParams {
+    kind: InterruptionKind,
+    reason: Option<String>,
+}
+
+fn interrupt_params(params: Option<Value>) -> Result<InterruptParams, RpcError> {
+    let params = params.ok_or_else(|| RpcError::new(INVALID_PARAMS, "missing field `kind`"))?;
+    serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
+
+/// Optional `minutes` parameter of `extend`
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ExtendParams {
+    minutes: Option<u32>,
+}
+
+fn extend_params(params: Option<Value>) -> Result<ExtendParams, RpcError> {
+    match params {
+        None | Some(Value::Null) => Ok(ExtendParams::default()),
+        Some(value) => serde_json::from_value(value).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string())),
+    }
+}
+
+/// Optional `confirmation` parameter of `abandon`
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AbandonParams {
+    confirmation: Option<String>,
+}
+
+fn abandon_params(params: Option<Value>) -> Result<AbandonParams, RpcError> {
+    match params {
+        None | Some(Value::Null) => Ok(AbandonParams::default()),
+        Some(value) => serde_json::from_value(value).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string())),
+    }
+}
+
+/// Parameters of `addNote`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NoteParams {
+    text: String,
+}
+
+fn note_params(params: Option<Value>) -> Result<NoteParams, RpcError> {
+    let params = params.ok_or_else(|| RpcError::new(INVALID_PARAMS, "missing field `text`"))?;
+    serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
+
+/// Method name and parameters for a timer command
+fn command_request(command: &PomodoroCommand) -> (&'static str, Value) {
+    match command {
+        PomodoroCommand::Start { project, task } => ("start", json!({ "project": project, "task": task })),
+        PomodoroCommand::Stop => ("stop", Value::Null),
+        PomodoroCommand::Toggle => ("toggle", Value::Null),
+        PomodoroCommand::Next => ("next", Value::Null),
+        PomodoroCommand::SetTask { project, task } => ("setTask", json!({ "project": project, "task": task })),
+        PomodoroCommand::Interrupt { kind, reason } => ("interrupt", json!({ "kind": kind, "reason": reason })),
+        PomodoroCommand::Extend { minutes } => ("extend", json!({ "minutes": minutes })),
+        PomodoroCommand::Abandon { confirmation } => ("abandon", json!({ "confirmation": confirmation })),
+        PomodoroCommand::AddNote { text } => ("addNote", json!({ "text": text })),
+        PomodoroCommand::Shutdown => ("shutdown", Value::Null),
+    }
+}
+
+/// Inverse of `command_request`; `None` for methods that are not timer commands
+fn parse_command(method: &str, params: Option<Value>) -> Result<Option<PomodoroCommand>, RpcError> {
+    let command = match method {
+        "start" => {
+            let TaskParams { project, task } = task_params(params)?;
+            PomodoroCommand::Start { project, task }
+        }
+        "stop" => PomodoroCommand::Stop,
+        "toggle" => PomodoroCommand::Toggle,
+        "next" => PomodoroCommand::Next,
+        "setTask" => {
+            let TaskParams { project, task } = task_params(params)?;
+            PomodoroCommand::SetTask { project, task }
+        }
+        "interrupt" => {
+            let InterruptParams { kind, reason } = interrupt_params(params)?;
+            PomodoroCommand::Interrupt { kind, reason }
+        }
+        "extend" => {
+            let ExtendParams { minutes } = extend_params(params)?;
+            PomodoroCommand::Extend { minutes }
+        }
+        "abandon" => {
+            let AbandonParams { confirmation } = abandon_params(params)?;
+            PomodoroCommand::Abandon { confirmation }
+        }
+        "addNote" => {
+            let NoteParams { text } = note_params(params)?;
+            PomodoroCommand::AddNote { text }
+        }
+        "shutdown" => PomodoroCommand::Shutdown,
+        _ => return Ok(None),
+    };
+    Ok(Some(command))
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message },
+    })
+}
+
+fn status_notification(status: &PomodoroStatus) -> Value {
+    json!({ "jsonrpc": "2.0", "method": "statusChanged", "params": status })
+}
+
+/// Protocol state for one client connection
+struct Session {
+    pomodoro: Arc<Mutex<Pomodoro>>,
+    shutdown: Arc<AtomicBool>,
+    subscribed: bool,
+}
+
+impl Session {
+    fn call(&mut self, method: &str, params: Option<Value>) -> Result<Value, RpcError> {
+        match method {
+            "initialize" => {
+                return Ok(json!({
+                    "protocolVersion": PROTOCOL_VERSION,
+                    "serverName": env!("CARGO_PKG_NAME"),
+                    "serverVersion": env!("CARGO_PKG_VERSION"),
+                }));
+            }
+            "status" => {}
+            "subscribe" => self.subscribed = true,
+            "unsubscribe" => self.subscribed = false,
+            _ => match parse_command(method, params)? {
+                Some(PomodoroCommand::Shutdown) => {
+                    // The owning process notices the flag and shuts down cleanly
+                    self.shutdown.store(true, Ordering::SeqCst);
+                }
+                Some(command) => {
+                    let mut pomodoro = self.pomodoro.lock().unwrap();
+                    pomodoro.apply(&command).map_err(|e| RpcError::new(TIMER_ERROR, e.to_string()))?;
+                }
+                None => return Err(RpcError::new(METHOD_NOT_FOUND, format!("Unknown method '{}'", method))),
+            },
+        }
+
+        // Every other method answers with the resulting timer status
+        let status = self.pomodoro.lock().unwrap().status();
+        serde_json::to_value(status).map_err(|e| RpcError::new(TIMER_ERROR, e.to_string()))
+    }
+
+    /// Handle one request object. Returns `None` for notifications, which get no reply.
+    fn handle_message(&mut self, message: Value) -> Option<Value> {
+        let (id, result) = match (message.get("jsonrpc"), message.get("method")) {
+            (Some(Value::String(version)), Some(Value::String(method))) if version == "2.0" => {
+                let method = method.clone();
+                let result = self.call(&method, message.get("params").cloned());
+                // Only a well-formed request without an id is a notification
+                (message.get("id").cloned()?, result)
+            }
+            // Invalid requests are always answered, with a null id if theirs can't be read
+            _ => (
+                message.get("id").cloned().unwrap_or(Value::Null),
+                Err(RpcError::new(INVALID_REQUEST, "Expected a JSON-RPC 2.0 request")),
+            ),
+        };
+
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(error) => error_response(id, error),
+        })
+    }
+
+    /// Handle one line, which holds either a single request or a batch
+    fn handle_line(&mut self, line: &str) -> Option<Value> {
+        match serde_json::from_str::<Value>(line) {
+            Ok(Value::Array(batch)) if !batch.is_empty() => {
+                let responses: Vec<Value> = batch.into_iter().filter_map(|m| self.handle_message(m)).collect();
+                (!responses.is_empty()).then_some(Value::Array(responses))
+            }
+            Ok(Value::Array(_)) => Some(error_response(Value::Null, RpcError::new(INVALID_REQUEST, "Empty batch"))),
+            Ok(message) => self.handle_message(message),
+            Err(e) => Some(error_response(Value::Null, RpcError::new(PARSE_ERROR, e.to_string()))),
+        }
+    }
+}
+
+/// Control socket served by the process that owns the timer.
+/// The task is stopped and the socket file removed when this is dropped,
+/// unless the socket came from systemd, which owns the file.
+pub struct ControlServer {
+    path: PathBuf,
+    handle: Option<JoinHandle<()>>,
+    owns_path: bool,
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            handle.abort();
+        }
+        if self.owns_path {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+#[cfg(unix)]
+mod platform {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use std::os::unix::io::FromRawFd;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
+    use tokio::net::{UnixListener, UnixStream};
+
+    /// Listening socket handed over by systemd socket activation, if any (see sd_listen_fds(3))
+    fn activated_listener() -> Option<std::os::unix::net::UnixListener> {
+        // Passed descriptors always start at 3
+        const SD_LISTEN_FDS_START: i32 = 3;
+
+        let pid: u32 = std::env::var("LISTEN_PID").ok()?.parse().ok()?;
+        let fds: i32 = std::env::var("LISTEN_FDS").ok()?.parse().ok()?;
+        if pid != std::process::id() || fds < 1 {
+            return None;
+        }
+
+        // Don't let child processes think the sockets are meant for them
+        std::env::remove_var("LISTEN_PID");
+        std::env::remove_var("LISTEN_FDS");
+        std::env::remove_var("LISTEN_FDNAMES");
+
+        // SAFETY: systemd passes us ownership of this descriptor and nothing else uses it
+        Some(unsafe { std::os::unix::net::UnixListener::from_raw_fd(SD_LISTEN_FDS_START) })
+    }
+
+    async fn write_message(writer: &mut OwnedWriteHalf, message: &Value) -> io::Result<()> {
+        writer.write_all(format!("{}\n", message).as_bytes()).await
+    }
+
+    /// Serve one client until it disconnects
+    async fn serve_connection(
+        stream: UnixStream,
+        mut session: Session,
+        mut status_rx: watch::Receiver<PomodoroStatus>,
+    ) {
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        let mut last_notified = status_rx.borrow_and_update().clone();
+
+        loop {
+            tokio::select! {
+                line = lines.next_line() => {
+                    let line = match line {
+                        Ok(Some(line)) => line,
+                        _ => break,
+                    };
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+
+                    let was_subscribed = session.subscribed;
+                    if let Some(response) = session.handle_line(&line) {
+                        if write_message(&mut writer, &response).await.is_err() {
+                            break;
+                        }
+                    }
+
+                    // Notifications start from the status the subscribe reply just reported
+                    if session.subscribed && !was_subscribed {
+                        last_notified = status_rx.borrow_and_update().clone();
+                    }
+                }
+
+                changed = status_rx.changed(), if session.subscribed => {
+                    if changed.is_err() {
+                        break;
+                    }
+
+                    let status = status_rx.borrow_and_update().clone();
+                    if status.is_transition_from(&last_notified) {
+                        if write_message(&mut writer, &status_notification(&status)).await.is_err() {
+                            break;
+                        }
+                        last_notified = status;
+                    }
+                }
+            }
+        }
+    }
+
+    impl ControlServer {
+        /// Start accepting clients on the systemd-activated socket, or bind `path`.
+        /// Callers must hold the instance lock, which makes it safe to replace a
+        /// socket file left behind by a crash.
+        pub fn spawn(
+            path: &Path,
+            pomodoro: Arc<Mutex<Pomodoro>>,
+            status_rx: watch::Receiver<PomodoroStatus>,
+            shutdown: Arc<AtomicBool>,
+        ) -> io::Result<Self> {
+            let (listener, owns_path) = match activated_listener() {
+                Some(listener) => {
+                    listener.set_nonblocking(true)?;
+                    (UnixListener::from_std(listener)?, false)
+                }
+                None => {
+                    // Something still accepting connections (e.g. a systemd socket unit) owns the path
+                    if std::os::unix::net::UnixStream::connect(path).is_ok() {
+                        return Err(io::Error::new(
+                            io::ErrorKind::AddrInUse,
+                            format!("{} is in use by another timer", path.display()),
+                        ));
+                    }
+                    let _ = std::fs::remove_file(path);
+                    let listener = UnixListener::bind(path)?;
+                    // Only the owner may control the timer, as with the socket unit's SocketMode=0600
+                    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+                    (listener, true)
+                }
+            };
+
+            let handle = tokio::spawn(async move {
+                loop {
+                    let stream = match listener.accept().await {
+                        Ok((stream, _)) => stream,
+                        Err(e) => {
+                            eprintln!("Control socket accept failed: {}", e);
+                            continue;
+                        }
+                    };
+
+                    let session = Session {
+                        pomodoro: pomodoro.clone(),
+                        shutdown: shutdown.clone(),
+                        subscribed: false,
+                    };
+                    tokio::spawn(serve_connection(stream, session, status_rx.clone()));
+                }
+            });
+
+            Ok(Self { path: path.to_path_buf(), handle: Some(handle), owns_path })
+        }
+    }
+
+    /// JSON-RPC connection to the control socket of a running timer
+    pub struct ControlClient {
+        reader: BufReader<OwnedReadHalf>,
+        writer: OwnedWriteHalf,
+        next_id: u64,
+    }
+
+    impl ControlClient {
+        pub async fn connect(path: &Path) -> Result<Self, ControlError> {
+            let (reader, writer) = UnixStream::connect(path).await?.into_split();
+            Ok(Self { reader: BufReader::new(reader), writer, next_id: 1 })
+        }
+
+        async fn call(&mut self, method: &str, params: Value) -> Result<Value, ControlError> {
+            let id = self.next_id;
+            self.next_id += 1;
+
+            let mut request = json!({ "jsonrpc": "2.0", "id": id, "method": method });
+            if !params.is_null() {
+                request["params"] = params;
+            }
+            write_message(&mut self.writer, &request).await?;
+
+            loop {
+                let mut line = String::new();
+                if self.reader.read_line(&mut line).await? == 0 {
+                    return Err(ControlError::Disconnected);
+                }
+
+                let response: Value = serde_json::from_str(&line)?;
+                // Skip anything that isn't the reply to this request, such as notifications
+                if response.get("id") != Some(&json!(id)) {
+                    continue;
+                }
+
+                if let Some(error) = response.get("error") {
+                    return Err(ControlError::Remote {
+                        code: error.get("code").and_then(Value::as_i64).unwrap_or(TIMER_ERROR),
+                        message: error.get("message").and_then(Value::as_str).unwrap_or("Unknown error").to_string(),
+                    });
+                }
+                return Ok(response.get("result").cloned().unwrap_or(Value::Null));
+            }
+        }
+
+        pub async fn status(&mut self) -> Result<PomodoroStatus, ControlError> {
+            Ok(serde_json::from_value(self.call("status", Value::Null).await?)?)
+        }
+
+        pub async fn send(&mut self, command: &PomodoroCommand) -> Result<PomodoroStatus, ControlError> {
+            let (method, params) = command_request(command);
+            Ok(serde_json::from_value(self.call(method, params).await?)?)
+        }
+
+        /// Relay protocol traffic between stdin/stdout and the socket until the timer closes
+        /// the connection, which it does once stdin has closed and every reply has been sent
+        pub async fn proxy_stdio(self) -> Result<(), ControlError> {
+            let Self { mut reader, mut writer, .. } = self;
+            let mut stdin = tokio::io::stdin();
+            let mut stdout = tokio::io::stdout();
+
+            let requests = async {
+                tokio::io::copy(&mut stdin, &mut writer).await?;
+                writer.shutdown().await?;
+                std::future::pending::<io::Result<()>>().await
+            };
+
+            tokio::select! {
+                result = tokio::io::copy(&mut reader, &mut stdout) => { result?; }
+                result = requests => { result?; }
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod platform {
+    use super::*;
+
+    fn unsupported() -> io::Error {
+        io::Error::new(io::ErrorKind::Unsupported, "control sockets are only supported on Unix")
+    }
+
+    impl ControlServer {
+        /// Without Unix sockets there is nothing to serve; the timer still runs locally
+        pub fn spawn(
+            path: &Path,
+            _pomodoro: Arc<Mutex<Pomodoro>>,
+            _status_rx: watch::Receiver<PomodoroStatus>,
+            _shutdown: Arc<AtomicBool>,
+        ) -> io::Result<Self> {
+            Ok(Self { path: path.to_path_buf(), handle: None, owns_path: false })
+        }
+    }
+
+    pub struct ControlClient;
+
+    impl ControlClient {
+        pub async fn connect(_path: &Path) -> Result<Self, ControlError> {
+            Err(ControlError::Io(unsupported()))
+        }
+
+        pub async fn status(&mut self) -> Result<PomodoroStatus, ControlError> {
+            Err(ControlError::Io(unsupported()))
+        }
+
+        pub async fn send(&mut self, _command: &PomodoroCommand) -> Result<PomodoroStatus, ControlError> {
+            Err(ControlError::Io(unsupported()))
+        }
+
+        pub async fn proxy_stdio(self) -> Result<(), ControlError> {
+            Err(ControlError::Io(unsupported()))
+        }
+    }
+}
+
+pub use platform::ControlClient;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+    use crate::notification::Notifier;
+    use crate::pomodoro::{PomodoroConfig, PomodoroState};
+    use tokio::sync::mpsc;
+
+    struct SilentNotifier;
+
+    impl Notifier for SilentNotifier {
+        fn notify(&self, _title: &str, _message: &str) {}
+    }
+
+    fn session() -> Session {
+        let database = Arc::new(Database::new(":memory:").unwrap());
+        let (log_tx, _) = mpsc::unbounded_channel();
+        let pomodoro = Pomodoro::new(PomodoroConfig::default(), database, Arc::new(SilentNotifier), log_tx);
+        Session {
+            pomodoro: Arc::new(Mutex::new(pomodoro)),
+            shutdown: Arc::new(AtomicBool::new(false)),
+            subscribed: false,
+        }
+    }
+
+    fn error_code(response: &Value) -> i64 {
+        response["error"]["code"].as_i64().unwrap()
+    }
+
+    #[test]
+    fn answers_requests_with_their_id() {
+        let response = session().handle_line(r#"{"jsonrpc":"2.0","id":"a","method":"status"}"#).unwrap();
+        assert_eq!(response["id"], "a");
+        assert_eq!(response["result"]["state"], "idle");
+    }
+
+    #[test]
+    fn commands_return_the_new_status() {
+        let mut session = session();
+        let line = r#"{"jsonrpc":"2.0","id":1,"method":"start","params":{"task":"Fix header"}}"#;
+        let response = session.handle_line(line).unwrap();
+        assert_eq!(response["result"]["state"], "work");
+        assert_eq!(response["result"]["task"], "Fix header");
+        assert_eq!(response["result"]["session_id"], 1);
+    }
+
+    #[test]
+    fn notifications_are_carried_out_without_a_reply() {
+        let mut session = session();
+        assert_eq!(session.handle_line(r#"{"jsonrpc":"2.0","method":"start"}"#), None);
+        assert_eq!(session.pomodoro.lock().unwrap().status().state, PomodoroState::Work);
+        assert_eq!(session.handle_line(r#"[{"jsonrpc":"2.0","method":"status"}]"#), None);
+    }
+
+    #[test]
+    fn unparsable_lines_are_parse_errors() {
+        let response = session().handle_line("{").unwrap();
+        assert_eq!(error_code(&response), PARSE_ERROR);
+        assert_eq!(response["id"], Value::Null);
+    }
+
+    #[test]
+    fn invalid_requests_are_answered_with_a_null_id() {
+        let mut session = session();
+        for line in [r#"{"foo":1}"#, r#"{"jsonrpc":"2.0"}"#, r#"{"jsonrpc":"1.0","method":"status"}"#, "1", "[]"] {
+            let response = session.handle_line(line).unwrap();
+            assert_eq!(error_code(&response), INVALID_REQUEST, "{}", line);
+            assert_eq!(response["id"], Value::Null, "{}", line);
+        }
+    }
+
+    #[test]
+    fn invalid_requests_keep_an_id_they_have() {
+        let response = session().handle_line(r#"{"jsonrpc":"2.0","id":5}"#).unwrap();
+        assert_eq!(error_code(&response), INVALID_REQUEST);
+        assert_eq!(response["id"], 5);
+    }
+
+    #[test]
+    fn batches_answer_each_request_in_order() {
+        let line = r#"[1, {"jsonrpc":"2.0","method":"status"}, {"jsonrpc":"2.0","id":2,"method":"status"}]"#;
+        let response = session().handle_line(line).unwrap();
+        let responses = response.as_array().unwrap();
+        assert_eq!(responses.len(), 2);
+        assert_eq!(error_code(&responses[0]), INVALID_REQUEST);
+        assert_eq!(responses[0]["id"], Value::Null);
+        assert_eq!(responses[1]["id"], 2);
+        assert!(responses[1].get("result").is_some());
+    }
+
+    #[test]
+    fn unknown_methods_are_not_found() {
+        let response = session().handle_line(r#"{"jsonrpc":"2.0","id":1,"method":"explode"}"#).unwrap();
+        assert_eq!(error_code(&response), METHOD_NOT_FOUND);
+        assert_eq!(response["id"], 1);
+    }
+
+    #[test]
+    fn bad_params_are_invalid() {
+        let mut session = session();
+        for line in [
+            r#"{"jsonrpc":"2.0","id":1,"method":"setTask","params":{"task":"x","colour":"red"}}"#,
+            r#"{"jsonrpc":"2.0","id":1,"method":"interrupt"}"#,
+            r#"{"jsonrpc":"2.0","id":1,"method":"interrupt","params":{"kind":"cosmic"}}"#,
+            r#"{"jsonrpc":"2.0","id":1,"method":"extend","params":{"minutes":-1}}"#,
+            r#"{"jsonrpc":"2.0","id":1,"method":"addNote","params":{}}"#,
+        ] {
+            let response = session.handle_line(line).unwrap();
+            assert_eq!(error_code(&response), INVALID_PARAMS, "{}", line);
+        }
+    }
+
+    #[test]
+    fn rejected_commands_are_timer_errors() {
+        let response = session().handle_line(r#"{"jsonrpc":"2.0","id":1,"method":"stop"}"#).unwrap();
+        assert_eq!(error_code(&response), TIMER_ERROR);
+        assert_eq!(response["error"]["message"], "Timer not running");
+    }
+
+    #[test]
+    fn shutdown_raises_the_flag() {
+        let mut session = session();
+        session.handle_line(r#"{"jsonrpc":"2.0","id":1,"method":"shutdown"}"#).unwrap();
+        assert!(session.shutdown.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn commands_round_trip_through_requests() {
+        let commands = [
+            PomodoroCommand::Start { project: Some("website".into()), task: None },
+            PomodoroCommand::Stop,
+            PomodoroCommand::Toggle,
+            PomodoroCommand::Next,
+            PomodoroCommand::SetTask { project: None, task: Some("Fix header".into()) },
+            PomodoroCommand::Interrupt { kind: InterruptionKind::External, reason: Some("phone".into()) },
+            PomodoroCommand::Extend { minutes: Some(5) },
+            PomodoroCommand::Abandon { confirmation: None },
+            PomodoroCommand::AddNote { text: "call the bank".into() },
+            PomodoroCommand::Shutdown,
+        ];
+        for command in commands {
+            let (method, params) = command_request(&command);
+            assert_eq!(parse_command(method, Some(params)).unwrap(), Some(command));
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn the_socket_is_private_to_its_owner() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join(format!("pomodoro-control-test-{}.sock", std::process::id()));
+        let session = session();
+        let (_, status_rx) = watch::channel(PomodoroStatus::default());
+        let server = ControlServer::spawn(&path, session.pomodoro, status_rx, session.shutdown).unwrap();
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        let mut client = ControlClient::connect(&path).await.unwrap();
+        assert_eq!(client.status().await.unwrap().state, PomodoroState::Idle);
+
+        drop(server);
+        assert!(!path.exists());
+    }
+}
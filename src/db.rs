@@ -1,7 +1,9 @@
-use rusqlite::{Connection, Result, params};
-use chrono::{DateTime, Local};
+use rusqlite::{Connection, OptionalExtension, Result, params};
+use chrono::{DateTime, Duration, Local};
 use thiserror::Error;
 
+use crate::pomodoro::InterruptionKind;
+
 #[derive(Error, Debug)]
 pub enum DatabaseError {
     #[error("SQLite error: {0}")]
@@ -22,14 +24,25 @@ pub struct PomodoroSession {
     pub end_time: Option<DateTime<Local>>,
     pub duration_seconds: i64,
     pub completed: bool,
-    pub session_type: String, // "work", "short_break", "long_break"
+    pub session_type: String, // "work", or the name of a work interval in a custom sequence
+    /// How the session ended; `None` while it's in progress
+    pub outcome: Option<SessionOutcome>,
+    /// Time actually spent focused, pauses excluded; `None` until the session ends
+    /// and for sessions recorded before pauses were tracked
+    pub focus_seconds: Option<i64>,
+    pub pause_count: i64,
+    /// Time worked past the planned length in overtime mode
+    pub overtime_seconds: i64,
 }
 
 #[derive(Debug)]
 pub struct StatsDaily {
     pub date: String,
     pub work_sessions: i64,
+    /// Minutes actually spent focused, not the planned length
     pub total_work_minutes: i64,
+    /// Part of the focus minutes worked past the planned length
+    pub overtime_minutes: i64,
     pub completed_work_sessions: i64,
     pub completion_rate: f64,
 }
@@ -37,7 +50,10 @@ pub struct StatsDaily {
 #[derive(Debug)]
 pub struct StatsSummary {
     pub total_work_sessions: i64,
+    /// Minutes actually spent focused, not the planned length
     pub total_work_minutes: i64,
+    /// Part of the focus minutes worked past the planned length
+    pub overtime_minutes: i64,
     pub completed_sessions: i64,
     pub completion_rate: f64,
     pub avg_sessions_per_day: f64,
@@ -53,6 +69,152 @@ pub struct SessionTypeSummary {
     pub completion_rate: f64,
 }
 
+/// How often, and by how much, intervals of one type were extended
+#[derive(Debug)]
+pub struct ExtensionSummary {
+    pub session_type: String,
+    pub count: i64,
+    pub total_minutes: i64,
+}
+
+/// Work sessions and their interruptions on one day
+#[derive(Debug)]
+pub struct InterruptionsDaily {
+    pub date: String,
+    pub work_sessions: i64,
+    pub internal: i64,
+    pub external: i64,
+}
+
+/// Interruptions logged during one hour of the day, over a range of days
+#[derive(Debug)]
+pub struct InterruptionsHourly {
+    pub hour: i64,
+    pub internal: i64,
+    pub external: i64,
+}
+
+/// A session left open by a timer that stopped without shutting down
+#[derive(Debug, Clone)]
+pub struct StaleSession {
+    pub id: i64,
+    pub session_type: String,
+    pub start_time: DateTime<Local>,
+    pub duration_seconds: i64,
+    pub pause_count: i64,
+    /// The recorded outcome once closed, otherwise the inferred one
+    pub outcome: SessionOutcome,
+    /// When it ended, recorded or inferred like `outcome`
+    pub end_time: DateTime<Local>,
+    /// Already closed automatically, waiting for review
+    pub recovered: bool,
+}
+
+/// Timer state persisted on shutdown so the next run can pick up where it left off
+#[derive(Debug, Clone)]
+pub struct TimerSnapshot {
+    pub state: String,
+    pub prev_state: Option<String>,
+    pub remaining_seconds: i64,
+    pub completed_pomodoros: i64,
+    pub session_id: Option<i64>,
+    pub task: Option<String>,
+    /// Position in a custom interval sequence
+    pub sequence_index: i64,
+    pub saved_at: DateTime<Local>,
+}
+
+/// A note jotted down during a session
+#[derive(Debug, Clone)]
+pub struct Note {
+    pub id: i64,
+    pub created_at: DateTime<Local>,
+    pub text: String,
+}
+
+/// What became of a note once it was reviewed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NoteTriage {
+    /// Taken on as the task to work on
+    Task,
+    Dismissed,
+}
+
+impl NoteTriage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NoteTriage::Task => "task",
+            NoteTriage::Dismissed => "dismissed",
+        }
+    }
+}
+
+/// SQL for the seconds a session was actually focused. Sessions recorded before pauses
+/// were tracked fall back to their wall-clock span, capped at the planned length, and
+/// sessions still in progress count once they end.
+const FOCUS_SECONDS: &str = "COALESCE(
+    focus_seconds,
+    CASE WHEN end_time IS NULL THEN 0
+    ELSE MIN(duration_seconds, CAST(round((julianday(end_time) - julianday(start_time)) * 86400) AS INTEGER))
+    END
+)";
+
+/// SQL condition for work sessions, whatever a custom sequence named their interval.
+/// Breaks, which older versions recorded as sessions too, are left out.
+const IS_WORK: &str = "session_type NOT IN ('short_break', 'long_break')";
+
+/// SQL for the day a session started on, so every per-day figure groups sessions alike
+const DAY: &str = "strftime('%Y-%m-%d', start_time)";
+
+/// Whether a session type is work, as `IS_WORK` decides in SQL
+pub fn is_work_type(session_type: &str) -> bool {
+    !matches!(session_type, "short_break" | "long_break")
+}
+
+/// How a work session ended
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SessionOutcome {
+    /// Ran for its full length
+    Completed,
+    /// Ended early by moving to the next interval
+    Skipped,
+    /// Ended early after interruptions were logged during it
+    Interrupted,
+    /// Still running or paused when the timer quit
+    Abandoned,
+}
+
+impl SessionOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionOutcome::Completed => "completed",
+            SessionOutcome::Skipped => "skipped",
+            SessionOutcome::Interrupted => "interrupted",
+            SessionOutcome::Abandoned => "abandoned",
+        }
+    }
+    
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "completed" => Some(SessionOutcome::Completed),
+            "skipped" => Some(SessionOutcome::Skipped),
+            "interrupted" => Some(SessionOutcome::Interrupted),
+            "abandoned" => Some(SessionOutcome::Abandoned),
+            _ => None,
+        }
+    }
+    
+    /// Human-readable name for display
+    pub fn label(&self) -> &'static str {
+        match self {
+            SessionOutcome::Completed => "Completed",
+            SessionOutcome::Skipped => "Skipped",
+            SessionOutcome::Interrupted => "Interrupted",
+            SessionOutcome::Abandoned => "Abandoned",
+        }
+    }
+}
+
 pub struct Database {
     conn: std::sync::Mutex<Connection>,
 }
@@ -69,7 +231,108 @@ impl Database {
                 end_time TEXT,
                 duration_seconds INTEGER NOT NULL,
                 completed BOOLEAN NOT NULL,
-                session_type TEXT NOT NULL
+                session_type TEXT NOT NULL,
+                focus_seconds INTEGER,
+                outcome TEXT,
+                recovered BOOLEAN NOT NULL DEFAULT 0,
+                overtime_seconds INTEGER NOT NULL DEFAULT 0
+            )",
+            [],
+        )?;
+        
+        add_column_if_missing(&conn, "pomodoro_sessions", "focus_seconds", "INTEGER")?;
+        add_column_if_missing(&conn, "pomodoro_sessions", "recovered", "BOOLEAN NOT NULL DEFAULT 0")?;
+        add_column_if_missing(&conn, "pomodoro_sessions", "overtime_seconds", "INTEGER NOT NULL DEFAULT 0")?;
+        if add_column_if_missing(&conn, "pomodoro_sessions", "outcome", "TEXT")? {
+            // Older versions only ever cancelled a session when the timer quit
+            conn.execute(
+                "UPDATE pomodoro_sessions
+                 SET outcome = CASE WHEN completed = 1 THEN 'completed' ELSE 'abandoned' END
+                 WHERE end_time IS NOT NULL",
+                [],
+            )?;
+        }
+        
+        // Pauses of a work session; `resumed_at` stays empty while the pause lasts
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS pauses (
+                id INTEGER PRIMARY KEY,
+                session_id INTEGER NOT NULL REFERENCES pomodoro_sessions(id),
+                paused_at TEXT NOT NULL,
+                resumed_at TEXT
+            )",
+            [],
+        )?;
+        
+        // Single-row table holding the last persisted timer state
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS timer_state (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                state TEXT NOT NULL,
+                prev_state TEXT,
+                remaining_seconds INTEGER NOT NULL,
+                completed_pomodoros INTEGER NOT NULL,
+                session_id INTEGER,
+                task TEXT,
+                saved_at TEXT NOT NULL,
+                sequence_index INTEGER NOT NULL DEFAULT 0
+            )",
+            [],
+        )?;
+        add_column_if_missing(&conn, "timer_state", "sequence_index", "INTEGER NOT NULL DEFAULT 0")?;
+        
+        // Free-form notes jotted down while the timer runs, linked to the work session they
+        // interrupted. `triage` stays empty until the note is turned into a task or dismissed.
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS notes (
+                id INTEGER PRIMARY KEY,
+                created_at TEXT NOT NULL,
+                text TEXT NOT NULL,
+                session_id INTEGER REFERENCES pomodoro_sessions(id),
+                triage TEXT
+            )",
+            [],
+        )?;
+        add_column_if_missing(&conn, "notes", "session_id", "INTEGER REFERENCES pomodoro_sessions(id)")?;
+        add_column_if_missing(&conn, "notes", "triage", "TEXT")?;
+        
+        // Times the terminal lost focus for longer than the auto-pause grace period.
+        // `action` is "paused" for a paused break or "interrupted" for a work session,
+        // which is linked through `session_id`.
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS focus_losses (
+                id INTEGER PRIMARY KEY,
+                session_id INTEGER REFERENCES pomodoro_sessions(id),
+                session_type TEXT NOT NULL,
+                action TEXT NOT NULL,
+                left_at TEXT NOT NULL,
+                returned_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        
+        // Interruptions logged during a work session, Cirillo style: `kind` is
+        // "internal" or "external", and `reason` is optional.
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS interruptions (
+                id INTEGER PRIMARY KEY,
+                session_id INTEGER NOT NULL REFERENCES pomodoro_sessions(id),
+                kind TEXT NOT NULL,
+                reason TEXT,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        
+        // Time added to an interval with `extend`. Only work sessions have a record to
+        // link to, so `session_type` says what was extended.
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS extensions (
+                id INTEGER PRIMARY KEY,
+                session_id INTEGER REFERENCES pomodoro_sessions(id),
+                session_type TEXT NOT NULL,
+                seconds INTEGER NOT NULL,
+                created_at TEXT NOT NULL
             )",
             [],
         )?;
@@ -77,6 +340,256 @@ impl Database {
         Ok(Self { conn: std::sync::Mutex::new(conn) })
     }
     
+    /// Save a note, linked to the work session the timer is running, if any
+    pub fn add_note(&self, text: &str, session_id: Option<i64>) -> Result<i64, DatabaseError> {
+        let conn = self.conn.lock().map_err(|_| DatabaseError::Initialization("Failed to lock database connection".to_string()))?;
+        
+        conn.execute(
+            "INSERT INTO notes (created_at, text, session_id) VALUES (?, ?, ?)",
+            params![Local::now().to_rfc3339(), text, session_id],
+        )?;
+        
+        Ok(conn.last_insert_rowid())
+    }
+    
+    /// Notes that haven't been triaged yet, oldest first
+    pub fn get_open_notes(&self) -> Result<Vec<Note>, DatabaseError> {
+        let conn = self.conn.lock().map_err(|_| DatabaseError::Initialization("Failed to lock database connection".to_string()))?;
+        
+        let mut stmt = conn.prepare(
+            "SELECT id, created_at, text FROM notes WHERE triage IS NULL ORDER BY id",
+        )?;
+        let notes = stmt
+            .query_map([], |row| {
+                let created_at: String = row.get(1)?;
+                Ok(Note {
+                    id: row.get(0)?,
+                    created_at: DateTime::parse_from_rfc3339(&created_at)
+                        .map(|time| time.with_timezone(&Local))
+                        .unwrap_or_else(|_| Local::now()),
+                    text: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+        
+        Ok(notes)
+    }
+    
+    /// Mark a note as dealt with. Returns `false` if there is no such note.
+    pub fn triage_note(&self, note_id: i64, triage: NoteTriage) -> Result<bool, DatabaseError> {
+        let conn = self.conn.lock().map_err(|_| DatabaseError::Initialization("Failed to lock database connection".to_string()))?;
+        
+        let updated = conn.execute(
+            "UPDATE notes SET triage = ? WHERE id = ?",
+            params![triage.as_str(), note_id],
+        )?;
+        
+        Ok(updated > 0)
+    }
+    
+    /// Record a focus loss against the open work session, if there is one
+    pub fn record_focus_loss(
+        &self,
+        session_type: &str,
+        action: &str,
+        left_at: DateTime<Local>,
+        returned_at: DateTime<Local>,
+    ) -> Result<i64, DatabaseError> {
+        let conn = self.conn.lock().map_err(|_| DatabaseError::Initialization("Failed to lock database connection".to_string()))?;
+        
+        // Only work sessions have records
+        let session_id = if session_type == "work" { open_work_session(&conn) } else { None };
+        
+        conn.execute(
+            "INSERT INTO focus_losses (session_id, session_type, action, left_at, returned_at)
+             VALUES (?, ?, ?, ?, ?)",
+            params![session_id, session_type, action, left_at.to_rfc3339(), returned_at.to_rfc3339()],
+        )?;
+        
+        Ok(conn.last_insert_rowid())
+    }
+    
+    pub fn add_interruption(
+        &self,
+        session_id: i64,
+        kind: InterruptionKind,
+        reason: Option<&str>,
+    ) -> Result<i64, DatabaseError> {
+        let conn = self.conn.lock().map_err(|_| DatabaseError::Initialization("Failed to lock database connection".to_string()))?;
+        
+        conn.execute(
+            "INSERT INTO interruptions (session_id, kind, reason, created_at) VALUES (?, ?, ?, ?)",
+            params![session_id, kind.as_str(), reason, Local::now().to_rfc3339()],
+        )?;
+        
+        Ok(conn.last_insert_rowid())
+    }
+    
+    pub fn add_extension(
+        &self,
+        session_id: Option<i64>,
+        session_type: &str,
+        seconds: i64,
+    ) -> Result<i64, DatabaseError> {
+        let conn = self.conn.lock().map_err(|_| DatabaseError::Initialization("Failed to lock database connection".to_string()))?;
+        
+        conn.execute(
+            "INSERT INTO extensions (session_id, session_type, seconds, created_at) VALUES (?, ?, ?, ?)",
+            params![session_id, session_type, seconds, Local::now().to_rfc3339()],
+        )?;
+        
+        Ok(conn.last_insert_rowid())
+    }
+    
+    /// Extensions so far, per interval type
+    pub fn get_extension_stats(&self) -> Result<Vec<ExtensionSummary>, DatabaseError> {
+        let conn = self.conn.lock().map_err(|_| DatabaseError::Initialization("Failed to lock database connection".to_string()))?;
+        
+        let mut stmt = conn.prepare(
+            "SELECT session_type, COUNT(*), CAST(SUM(seconds) / 60 AS INTEGER)
+             FROM extensions
+             GROUP BY session_type
+             ORDER BY COUNT(*) DESC",
+        )?;
+        
+        let extensions = stmt.query_map([], |row| {
+            Ok(ExtensionSummary {
+                session_type: row.get(0)?,
+                count: row.get(1)?,
+                total_minutes: row.get(2)?,
+            })
+        })?;
+        
+        let mut result = Vec::new();
+        for extension in extensions {
+            result.push(extension?);
+        }
+        
+        Ok(result)
+    }
+    
+    /// Work sessions per day over the last N days, with the interruptions logged during them
+    pub fn get_interruption_daily_stats(&self, days: i64) -> Result<Vec<InterruptionsDaily>, DatabaseError> {
+        let conn = self.conn.lock().map_err(|_| DatabaseError::Initialization("Failed to lock database connection".to_string()))?;
+        
+        let mut stmt = conn.prepare(&format!(
+            "SELECT
+                {DAY} as day,
+                COUNT(*) as work_sessions,
+                COALESCE(SUM(i.internal), 0) as internal,
+                COALESCE(SUM(i.external), 0) as external
+            FROM pomodoro_sessions s
+            LEFT JOIN (
+                SELECT
+                    session_id,
+                    SUM(CASE WHEN kind = 'internal' THEN 1 ELSE 0 END) as internal,
+                    SUM(CASE WHEN kind = 'external' THEN 1 ELSE 0 END) as external
+                FROM interruptions
+                GROUP BY session_id
+            ) i ON i.session_id = s.id
+            WHERE {IS_WORK}
+              AND julianday(s.start_time) >= julianday('now', '-' || ? || ' days')
+            GROUP BY day
+            ORDER BY day DESC"
+        ))?;
+        
+        let stats = stmt
+            .query_map(params![days], |row| {
+                Ok(InterruptionsDaily {
+                    date: row.get(0)?,
+                    work_sessions: row.get(1)?,
+                    internal: row.get(2)?,
+                    external: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+        
+        Ok(stats)
+    }
+    
+    /// Interruptions over the last N days by local hour of the day, for hours that had any
+    pub fn get_interruption_hourly_stats(&self, days: i64) -> Result<Vec<InterruptionsHourly>, DatabaseError> {
+        let conn = self.conn.lock().map_err(|_| DatabaseError::Initialization("Failed to lock database connection".to_string()))?;
+        
+        let mut stmt = conn.prepare(
+            "SELECT
+                CAST(substr(created_at, 12, 2) AS INTEGER) as hour,
+                SUM(CASE WHEN kind = 'internal' THEN 1 ELSE 0 END) as internal,
+                SUM(CASE WHEN kind = 'external' THEN 1 ELSE 0 END) as external
+            FROM interruptions
+            WHERE julianday(created_at) >= julianday('now', '-' || ? || ' days')
+            GROUP BY hour
+            ORDER BY hour"
+        )?;
+        
+        let stats = stmt
+            .query_map(params![days], |row| {
+                Ok(InterruptionsHourly {
+                    hour: row.get(0)?,
+                    internal: row.get(1)?,
+                    external: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+        
+        Ok(stats)
+    }
+    
+    pub fn save_timer_state(&self, snapshot: &TimerSnapshot) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().map_err(|_| DatabaseError::Initialization("Failed to lock database connection".to_string()))?;
+        
+        conn.execute(
+            "INSERT OR REPLACE INTO timer_state
+                (id, state, prev_state, remaining_seconds, completed_pomodoros, session_id, task, saved_at, sequence_index)
+             VALUES (1, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                snapshot.state,
+                snapshot.prev_state,
+                snapshot.remaining_seconds,
+                snapshot.completed_pomodoros,
+                snapshot.session_id,
+                snapshot.task,
+                snapshot.saved_at.to_rfc3339(),
+                snapshot.sequence_index,
+            ],
+        )?;
+        
+        Ok(())
+    }
+    
+    pub fn load_timer_state(&self) -> Result<Option<TimerSnapshot>, DatabaseError> {
+        let conn = self.conn.lock().map_err(|_| DatabaseError::Initialization("Failed to lock database connection".to_string()))?;
+        
+        let mut stmt = conn.prepare(
+            "SELECT state, prev_state, remaining_seconds, completed_pomodoros, session_id, task, saved_at, sequence_index
+             FROM timer_state
+             WHERE id = 1",
+        )?;
+        
+        let mut rows = stmt.query_map([], |row| {
+            let saved_at_str: String = row.get(6)?;
+            let saved_at = DateTime::parse_from_rfc3339(&saved_at_str)
+                .map(|dt| dt.with_timezone(&Local))
+                .unwrap_or_else(|_| Local::now());
+            
+            Ok(TimerSnapshot {
+                state: row.get(0)?,
+                prev_state: row.get(1)?,
+                remaining_seconds: row.get(2)?,
+                completed_pomodoros: row.get(3)?,
+                session_id: row.get(4)?,
+                task: row.get(5)?,
+                sequence_index: row.get(7)?,
+                saved_at,
+            })
+        })?;
+        
+        match rows.next() {
+            Some(snapshot) => Ok(Some(snapshot?)),
+            None => Ok(None),
+        }
+    }
+    
     pub fn start_session(&self, session_type: &str, duration_seconds: i64) -> Result<i64, DatabaseError> {
         let now = Local::now();
         let conn = self.conn.lock().map_err(|_| DatabaseError::Initialization("Failed to lock database connection".to_string()))?;
@@ -90,28 +603,139 @@ impl Database {
         Ok(conn.last_insert_rowid())
     }
     
-    pub fn complete_session(&self, session_id: i64) -> Result<(), DatabaseError> {
-        let now = Local::now();
+    pub fn end_session(&self, session_id: i64, outcome: SessionOutcome) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().map_err(|_| DatabaseError::Initialization("Failed to lock database connection".to_string()))?;
+        close_session(&conn, session_id, outcome, Local::now(), false)
+    }
+    
+    /// Store how long a session ran past its planned length
+    pub fn record_overtime(&self, session_id: i64, overtime_seconds: i64) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().map_err(|_| DatabaseError::Initialization("Failed to lock database connection".to_string()))?;
+        conn.execute(
+            "UPDATE pomodoro_sessions SET overtime_seconds = ? WHERE id = ?",
+            params![overtime_seconds, session_id],
+        )?;
+        Ok(())
+    }
+    
+    /// Store the length a session actually ran, for flowtime sessions planned without one
+    pub fn set_session_duration(&self, session_id: i64, duration_seconds: i64) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().map_err(|_| DatabaseError::Initialization("Failed to lock database connection".to_string()))?;
+        conn.execute(
+            "UPDATE pomodoro_sessions SET duration_seconds = ? WHERE id = ?",
+            params![duration_seconds, session_id],
+        )?;
+        Ok(())
+    }
+    
+    /// Work sessions left behind by a timer that stopped without shutting down: those still
+    /// open, except `live_session_id`, with an inferred outcome, followed by those already
+    /// closed by `recover_stale_sessions` that haven't been reviewed
+    pub fn get_stale_sessions(&self, live_session_id: Option<i64>) -> Result<Vec<StaleSession>, DatabaseError> {
+        let conn = self.conn.lock().map_err(|_| DatabaseError::Initialization("Failed to lock database connection".to_string()))?;
+        
+        let mut stmt = conn.prepare(
+            "SELECT id, session_type, start_time, end_time, duration_seconds, outcome,
+                (SELECT COUNT(*) FROM pauses WHERE pauses.session_id = pomodoro_sessions.id) as pause_count
+             FROM pomodoro_sessions
+             WHERE (end_time IS NULL AND id IS NOT ?) OR recovered = 1
+             ORDER BY end_time IS NOT NULL, id",
+        )?;
+        let rows = stmt
+            .query_map(params![live_session_id], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                    row.get::<_, i64>(4)?,
+                    row.get::<_, Option<String>>(5)?,
+                    row.get::<_, i64>(6)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>>>()?;
+        
+        let mut sessions = Vec::new();
+        for (id, session_type, start_time, end_time, duration_seconds, outcome, pause_count) in rows {
+            let start_time = parse_time(&start_time);
+            let recorded = end_time.zip(outcome.as_deref().and_then(SessionOutcome::from_name));
+            let recovered = recorded.is_some();
+            let (outcome, end_time) = match recorded {
+                Some((end_time, outcome)) => (outcome, parse_time(&end_time)),
+                None => infer_outcome(&conn, id, start_time, duration_seconds)?,
+            };
+            
+            sessions.push(StaleSession {
+                id,
+                session_type,
+                start_time,
+                duration_seconds,
+                pause_count,
+                outcome,
+                end_time,
+                recovered,
+            });
+        }
+        
+        Ok(sessions)
+    }
+    
+    /// The work session a running timer is timing, if any
+    pub fn get_open_work_session(&self) -> Result<Option<i64>, DatabaseError> {
+        let conn = self.conn.lock().map_err(|_| DatabaseError::Initialization("Failed to lock database connection".to_string()))?;
+        Ok(open_work_session(&conn))
+    }
+    
+    /// Close a stale session, or change the outcome of one closed by `recover_stale_sessions`.
+    /// `recovered` marks it as still waiting for review.
+    pub fn close_stale_session(&self, session: &StaleSession, outcome: SessionOutcome, recovered: bool) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().map_err(|_| DatabaseError::Initialization("Failed to lock database connection".to_string()))?;
+        close_session(&conn, session.id, outcome, session.end_time, recovered)
+    }
+    
+    /// Close every open session but `kept_session_id` with its inferred outcome. Only call this
+    /// while holding the instance lock, when no live timer can own a session. Returns how many were closed.
+    pub fn recover_stale_sessions(&self, kept_session_id: Option<i64>) -> Result<usize, DatabaseError> {
+        let stale: Vec<StaleSession> = self.get_stale_sessions(kept_session_id)?.into_iter().filter(|s| !s.recovered).collect();
+        for session in &stale {
+            self.close_stale_session(session, session.outcome, true)?;
+        }
+        Ok(stale.len())
+    }
+    
+    /// Whether interruptions were logged, or focus was lost, during a session
+    pub fn was_interrupted(&self, session_id: i64) -> Result<bool, DatabaseError> {
+        let conn = self.conn.lock().map_err(|_| DatabaseError::Initialization("Failed to lock database connection".to_string()))?;
+        
+        let interrupted = conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM interruptions WHERE session_id = ?1)
+                 OR EXISTS (SELECT 1 FROM focus_losses WHERE session_id = ?1 AND action = 'interrupted')",
+            params![session_id],
+            |row| row.get(0),
+        )?;
+        
+        Ok(interrupted)
+    }
+    
+    /// Note that a work session was paused
+    pub fn start_pause(&self, session_id: i64) -> Result<(), DatabaseError> {
         let conn = self.conn.lock().map_err(|_| DatabaseError::Initialization("Failed to lock database connection".to_string()))?;
         
         conn.execute(
-            "UPDATE pomodoro_sessions SET end_time = ?, completed = 1 WHERE id = ?",
-            params![now.to_rfc3339(), session_id],
+            "INSERT INTO pauses (session_id, paused_at) VALUES (?, ?)",
+            params![session_id, Local::now().to_rfc3339()],
         )?;
         
         Ok(())
     }
     
-    // We'll keep this method but mark it as allowed dead code
-    // in case we need it in future versions
-    #[allow(dead_code)]
-    pub fn cancel_session(&self, session_id: i64) -> Result<(), DatabaseError> {
-        let now = Local::now();
+    /// Note that a work session was resumed, closing its open pause if there is one
+    pub fn end_pause(&self, session_id: i64) -> Result<(), DatabaseError> {
         let conn = self.conn.lock().map_err(|_| DatabaseError::Initialization("Failed to lock database connection".to_string()))?;
         
         conn.execute(
-            "UPDATE pomodoro_sessions SET end_time = ?, completed = 0 WHERE id = ?",
-            params![now.to_rfc3339(), session_id],
+            "UPDATE pauses SET resumed_at = ? WHERE session_id = ? AND resumed_at IS NULL",
+            params![Local::now().to_rfc3339(), session_id],
         )?;
         
         Ok(())
@@ -121,7 +745,9 @@ impl Database {
         let conn = self.conn.lock().map_err(|_| DatabaseError::Initialization("Failed to lock database connection".to_string()))?;
         
         let mut stmt = conn.prepare(
-            "SELECT id, start_time, end_time, duration_seconds, completed, session_type 
+            "SELECT id, start_time, end_time, duration_seconds, completed, session_type, outcome, focus_seconds,
+                (SELECT COUNT(*) FROM pauses WHERE pauses.session_id = pomodoro_sessions.id) as pause_count,
+                overtime_seconds
              FROM pomodoro_sessions 
              ORDER BY start_time DESC 
              LIMIT ?",
@@ -149,6 +775,10 @@ impl Database {
                 duration_seconds: row.get(3)?,
                 completed: row.get(4)?,
                 session_type: row.get(5)?,
+                outcome: row.get::<_, Option<String>>(6)?.as_deref().and_then(SessionOutcome::from_name),
+                focus_seconds: row.get(7)?,
+                pause_count: row.get(8)?,
+                overtime_seconds: row.get(9)?,
             })
         })?;
         
@@ -164,30 +794,33 @@ impl Database {
         let conn = self.conn.lock().map_err(|_| DatabaseError::Initialization("Failed to lock database connection".to_string()))?;
         
         // Get stats grouped by day for the last N days
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare(&format!(
             "SELECT 
-                strftime('%Y-%m-%d', start_time) as day,
+                {DAY} as day,
                 COUNT(*) as total_sessions,
-                SUM(CASE WHEN session_type = 'work' THEN 1 ELSE 0 END) as work_sessions,
-                CAST(SUM(CASE WHEN session_type = 'work' THEN duration_seconds ELSE 0 END) / 60 AS INTEGER) as work_minutes,
-                SUM(CASE WHEN session_type = 'work' AND completed = 1 THEN 1 ELSE 0 END) as completed_work,
+                SUM(CASE WHEN {IS_WORK} THEN 1 ELSE 0 END) as work_sessions,
+                CAST(SUM(CASE WHEN {IS_WORK} THEN {} ELSE 0 END) / 60 AS INTEGER) as work_minutes,
+                SUM(CASE WHEN {IS_WORK} AND outcome = 'completed' THEN 1 ELSE 0 END) as completed_work,
                 CASE 
-                    WHEN SUM(CASE WHEN session_type = 'work' THEN 1 ELSE 0 END) > 0 
-                    THEN CAST(SUM(CASE WHEN session_type = 'work' AND completed = 1 THEN 1 ELSE 0 END) AS FLOAT) / 
-                         SUM(CASE WHEN session_type = 'work' THEN 1 ELSE 0 END)
+                    WHEN SUM(CASE WHEN {IS_WORK} AND outcome IS NOT NULL THEN 1 ELSE 0 END) > 0 
+                    THEN CAST(SUM(CASE WHEN {IS_WORK} AND outcome = 'completed' THEN 1 ELSE 0 END) AS FLOAT) / 
+                         SUM(CASE WHEN {IS_WORK} AND outcome IS NOT NULL THEN 1 ELSE 0 END)
                     ELSE 0
-                END as completion_rate
+                END as completion_rate,
+                CAST(SUM(CASE WHEN {IS_WORK} THEN overtime_seconds ELSE 0 END) / 60 AS INTEGER) as overtime_minutes
             FROM pomodoro_sessions
             WHERE start_time >= datetime('now', '-' || ? || ' days')
             GROUP BY day
-            ORDER BY day DESC"
-        )?;
+            ORDER BY day DESC",
+            FOCUS_SECONDS
+        ))?;
         
         let daily_stats = stmt.query_map(params![days], |row| {
             Ok(StatsDaily {
                 date: row.get(0)?,
                 work_sessions: row.get(2)?,
                 total_work_minutes: row.get(3)?,
+                overtime_minutes: row.get(6)?,
                 completed_work_sessions: row.get(4)?,
                 completion_rate: row.get(5)?,
             })
@@ -205,25 +838,27 @@ impl Database {
         let conn = self.conn.lock().map_err(|_| DatabaseError::Initialization("Failed to lock database connection".to_string()))?;
         
         // Get overall summary stats
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare(&format!(
             "SELECT 
-                COUNT(CASE WHEN session_type = 'work' THEN 1 ELSE NULL END) as total_work_sessions,
-                CAST(SUM(CASE WHEN session_type = 'work' THEN duration_seconds ELSE 0 END) / 60 AS INTEGER) as total_work_minutes,
-                COUNT(CASE WHEN session_type = 'work' AND completed = 1 THEN 1 ELSE NULL END) as completed_sessions,
+                COUNT(CASE WHEN {IS_WORK} THEN 1 ELSE NULL END) as total_work_sessions,
+                CAST(SUM(CASE WHEN {IS_WORK} THEN {} ELSE 0 END) / 60 AS INTEGER) as total_work_minutes,
+                COUNT(CASE WHEN {IS_WORK} AND outcome = 'completed' THEN 1 ELSE NULL END) as completed_sessions,
                 CASE 
-                    WHEN COUNT(CASE WHEN session_type = 'work' THEN 1 ELSE NULL END) > 0 
-                    THEN CAST(COUNT(CASE WHEN session_type = 'work' AND completed = 1 THEN 1 ELSE NULL END) AS FLOAT) / 
-                         COUNT(CASE WHEN session_type = 'work' THEN 1 ELSE NULL END)
+                    WHEN COUNT(CASE WHEN {IS_WORK} AND outcome IS NOT NULL THEN 1 ELSE NULL END) > 0 
+                    THEN CAST(COUNT(CASE WHEN {IS_WORK} AND outcome = 'completed' THEN 1 ELSE NULL END) AS FLOAT) / 
+                         COUNT(CASE WHEN {IS_WORK} AND outcome IS NOT NULL THEN 1 ELSE NULL END)
                     ELSE 0
                 END as completion_rate,
                 CASE 
-                    WHEN COUNT(DISTINCT strftime('%Y-%m-%d', start_time)) > 0 
-                    THEN CAST(COUNT(CASE WHEN session_type = 'work' THEN 1 ELSE NULL END) AS FLOAT) / 
-                         COUNT(DISTINCT strftime('%Y-%m-%d', start_time))
+                    WHEN COUNT(DISTINCT {DAY}) > 0 
+                    THEN CAST(COUNT(CASE WHEN {IS_WORK} THEN 1 ELSE NULL END) AS FLOAT) / 
+                         COUNT(DISTINCT {DAY})
                     ELSE 0
-                END as avg_sessions_per_day
-            FROM pomodoro_sessions"
-        )?;
+                END as avg_sessions_per_day,
+                CAST(SUM(CASE WHEN {IS_WORK} THEN overtime_seconds ELSE 0 END) / 60 AS INTEGER) as overtime_minutes
+            FROM pomodoro_sessions",
+            FOCUS_SECONDS
+        ))?;
         
         let mut summary = stmt.query_map([], |row| {
             Ok(StatsSummary {
@@ -232,17 +867,18 @@ impl Database {
                 completed_sessions: row.get(2)?,
                 completion_rate: row.get(3)?,
                 avg_sessions_per_day: row.get(4)?,
+                overtime_minutes: row.get(5)?,
                 longest_streak_days: 0, // Will calculate below
                 current_streak_days: 0,  // Will calculate below
             })
         })?.next().ok_or(DatabaseError::Initialization("Failed to get summary stats".into()))??;
         
-        // Calculate streaks
-        let mut streak_stmt = conn.prepare(
+        // Calculate streaks over days with at least one completed work session
+        let mut streak_stmt = conn.prepare(&format!(
             "WITH dates AS (
-                SELECT DISTINCT strftime('%Y-%m-%d', start_time) as day
+                SELECT DISTINCT {DAY} as day
                 FROM pomodoro_sessions
-                WHERE session_type = 'work'
+                WHERE {IS_WORK} AND outcome = 'completed'
                 ORDER BY day
             ),
             gaps AS (
@@ -270,7 +906,7 @@ impl Database {
                 (SELECT streak_length FROM streak_lengths 
                  WHERE last_day = (SELECT MAX(day) FROM dates)) AS current_streak
             FROM streak_lengths"
-        )?;
+        ))?;
         
         let streak_result = streak_stmt.query_row([], |row| {
             let longest: Result<i64, _> = row.get(0);
@@ -287,20 +923,22 @@ impl Database {
     pub fn get_session_type_stats(&self) -> Result<Vec<SessionTypeSummary>, DatabaseError> {
         let conn = self.conn.lock().map_err(|_| DatabaseError::Initialization("Failed to lock database connection".to_string()))?;
         
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare(&format!(
             "SELECT 
                 session_type,
                 COUNT(*) as count,
-                CAST(SUM(duration_seconds) / 60 AS INTEGER) as total_minutes,
+                CAST(SUM({}) / 60 AS INTEGER) as total_minutes,
                 CASE 
-                    WHEN COUNT(*) > 0 
-                    THEN CAST(SUM(CASE WHEN completed = 1 THEN 1 ELSE 0 END) AS FLOAT) / COUNT(*)
+                    WHEN SUM(CASE WHEN outcome IS NOT NULL THEN 1 ELSE 0 END) > 0 
+                    THEN CAST(SUM(CASE WHEN outcome = 'completed' THEN 1 ELSE 0 END) AS FLOAT) / 
+                         SUM(CASE WHEN outcome IS NOT NULL THEN 1 ELSE 0 END)
                     ELSE 0
                 END as completion_rate
             FROM pomodoro_sessions
             GROUP BY session_type
-            ORDER BY count DESC"
-        )?;
+            ORDER BY count DESC",
+            FOCUS_SECONDS
+        ))?;
         
         let type_stats = stmt.query_map([], |row| {
             Ok(SessionTypeSummary {
@@ -318,4 +956,224 @@ impl Database {
         
         Ok(result)
     }
+    
+    /// Run raw SQL, so tests can set up (or break) the database
+    #[cfg(test)]
+    pub(crate) fn execute_batch(&self, sql: &str) -> Result<(), DatabaseError> {
+        let conn = self.conn.lock().map_err(|_| DatabaseError::Initialization("Failed to lock database connection".to_string()))?;
+        conn.execute_batch(sql)?;
+        Ok(())
+    }
+}
+
+/// Close a session at `end_time` along with any pause left open, and store how long it
+/// was actually focused: the time from its start to `end_time` minus the time spent paused
+fn close_session(
+    conn: &Connection,
+    session_id: i64,
+    outcome: SessionOutcome,
+    end_time: DateTime<Local>,
+    recovered: bool,
+) -> Result<(), DatabaseError> {
+    let end_time = end_time.to_rfc3339();
+    
+    conn.execute(
+        "UPDATE pauses SET resumed_at = ? WHERE session_id = ? AND resumed_at IS NULL",
+        params![end_time, session_id],
+    )?;
+    conn.execute(
+        "UPDATE pomodoro_sessions SET
+            end_time = ?1,
+            completed = ?2,
+            outcome = ?3,
+            recovered = ?4,
+            focus_seconds = MAX(0,
+                CAST(round((julianday(?1) - julianday(start_time)) * 86400) AS INTEGER)
+                - (SELECT CAST(COALESCE(round(SUM(julianday(resumed_at) - julianday(paused_at)) * 86400), 0) AS INTEGER)
+                   FROM pauses WHERE session_id = ?5))
+         WHERE id = ?5",
+        params![end_time, outcome == SessionOutcome::Completed, outcome.as_str(), recovered, session_id],
+    )?;
+    
+    Ok(())
+}
+
+/// Best guess at how an open session ended when its timer died, from its pauses and age.
+/// A session that was paused was abandoned when the pause began. One that was running
+/// is taken to have completed when its time would have run out, if that moment has
+/// passed; otherwise it's abandoned now. A flowtime session has no planned end, so
+/// one left running is abandoned now too.
+fn infer_outcome(
+    conn: &Connection,
+    session_id: i64,
+    start_time: DateTime<Local>,
+    duration_seconds: i64,
+) -> Result<(SessionOutcome, DateTime<Local>), DatabaseError> {
+    let open_pause: Option<String> = conn
+        .query_row(
+            "SELECT paused_at FROM pauses WHERE session_id = ? AND resumed_at IS NULL ORDER BY id DESC LIMIT 1",
+            params![session_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    if let Some(paused_at) = open_pause {
+        return Ok((SessionOutcome::Abandoned, parse_time(&paused_at)));
+    }
+    
+    if duration_seconds == 0 {
+        return Ok((SessionOutcome::Abandoned, Local::now()));
+    }
+    
+    let paused_seconds: i64 = conn.query_row(
+        "SELECT CAST(COALESCE(round(SUM(julianday(resumed_at) - julianday(paused_at)) * 86400), 0) AS INTEGER)
+         FROM pauses WHERE session_id = ?",
+        params![session_id],
+        |row| row.get(0),
+    )?;
+    let due = start_time + Duration::seconds(duration_seconds + paused_seconds);
+    let now = Local::now();
+    
+    Ok(if due <= now { (SessionOutcome::Completed, due) } else { (SessionOutcome::Abandoned, now) })
+}
+
+fn parse_time(text: &str) -> DateTime<Local> {
+    DateTime::parse_from_rfc3339(text)
+        .map(|time| time.with_timezone(&Local))
+        .unwrap_or_else(|_| Local::now())
+}
+
+/// The work session in progress: the latest one without an end time
+fn open_work_session(conn: &Connection) -> Option<i64> {
+    conn.query_row(
+        &format!(
+            "SELECT id FROM pomodoro_sessions
+             WHERE {IS_WORK} AND end_time IS NULL
+             ORDER BY id DESC LIMIT 1"
+        ),
+        [],
+        |row| row.get(0),
+    )
+    .ok()
+}
+
+/// Bring a table created by an older version up to date. Returns whether the column was added.
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<bool, DatabaseError> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>>>()?
+        .iter()
+        .any(|name| name == column);
+    
+    if !exists {
+        conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition), [])?;
+    }
+    Ok(!exists)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// An open work session that started `minutes_ago`, planned to last `duration_seconds`
+    fn open_session(database: &Database, minutes_ago: i64, duration_seconds: i64) -> (i64, DateTime<Local>) {
+        let start_time = Local::now() - Duration::minutes(minutes_ago);
+        let id = database.start_session("work", duration_seconds).unwrap();
+        database
+            .execute_batch(&format!("UPDATE pomodoro_sessions SET start_time = '{}' WHERE id = {}", start_time.to_rfc3339(), id))
+            .unwrap();
+        (id, start_time)
+    }
+    
+    /// A pause of `id` that began at `paused_at` and lasted `minutes`, or is still open
+    fn pause(database: &Database, id: i64, paused_at: DateTime<Local>, minutes: Option<i64>) {
+        let resumed_at = minutes.map_or("NULL".to_string(), |minutes| format!("'{}'", (paused_at + Duration::minutes(minutes)).to_rfc3339()));
+        database
+            .execute_batch(&format!(
+                "INSERT INTO pauses (session_id, paused_at, resumed_at) VALUES ({}, '{}', {})",
+                id,
+                paused_at.to_rfc3339(),
+                resumed_at
+            ))
+            .unwrap();
+    }
+    
+    fn stale(database: &Database, id: i64) -> StaleSession {
+        database.get_stale_sessions(None).unwrap().into_iter().find(|session| session.id == id).unwrap()
+    }
+    
+    #[test]
+    fn a_session_left_paused_was_abandoned_when_the_pause_began() {
+        let database = Database::new(":memory:").unwrap();
+        let (id, start_time) = open_session(&database, 60, 25 * 60);
+        pause(&database, id, start_time + Duration::minutes(5), Some(2));
+        pause(&database, id, start_time + Duration::minutes(10), None);
+        
+        let session = stale(&database, id);
+        assert_eq!(session.outcome, SessionOutcome::Abandoned);
+        assert_eq!(session.end_time, start_time + Duration::minutes(10));
+        assert_eq!(session.pause_count, 2);
+        assert!(!session.recovered);
+    }
+    
+    #[test]
+    fn a_session_past_its_length_completed_when_it_was_due() {
+        let database = Database::new(":memory:").unwrap();
+        let (id, start_time) = open_session(&database, 60, 25 * 60);
+        // Pauses push back when the session was due
+        pause(&database, id, start_time + Duration::minutes(5), Some(10));
+        
+        let session = stale(&database, id);
+        assert_eq!(session.outcome, SessionOutcome::Completed);
+        assert_eq!(session.end_time, start_time + Duration::minutes(35));
+    }
+    
+    #[test]
+    fn a_session_within_its_length_was_abandoned() {
+        let database = Database::new(":memory:").unwrap();
+        let (id, _) = open_session(&database, 10, 25 * 60);
+        let (flowtime_id, _) = open_session(&database, 60, 0);
+        
+        let before = Local::now();
+        for id in [id, flowtime_id] {
+            let session = stale(&database, id);
+            assert_eq!(session.outcome, SessionOutcome::Abandoned);
+            assert!(session.end_time >= before);
+        }
+    }
+    
+    #[test]
+    fn an_old_session_keeps_its_own_end() {
+        let database = Database::new(":memory:").unwrap();
+        let (id, start_time) = open_session(&database, 3 * 24 * 60, 25 * 60);
+        
+        assert_eq!(database.recover_stale_sessions(None).unwrap(), 1);
+        
+        let session = stale(&database, id);
+        assert!(session.recovered);
+        assert_eq!(session.outcome, SessionOutcome::Completed);
+        assert_eq!(session.end_time, start_time + Duration::minutes(25));
+        let recorded = database.get_session_stats(1).unwrap().remove(0);
+        assert_eq!(recorded.focus_seconds, Some(25 * 60));
+        assert!(recorded.completed);
+    }
+    
+    #[test]
+    fn recovery_spares_the_kept_session() {
+        let database = Database::new(":memory:").unwrap();
+        let (stale_id, _) = open_session(&database, 60, 25 * 60);
+        let (kept_id, _) = open_session(&database, 5, 25 * 60);
+        
+        assert_eq!(database.recover_stale_sessions(Some(kept_id)).unwrap(), 1);
+        // Closed sessions wait for review without being closed again
+        assert_eq!(database.recover_stale_sessions(Some(kept_id)).unwrap(), 0);
+        
+        let sessions = database.get_stale_sessions(Some(kept_id)).unwrap();
+        assert_eq!(sessions.iter().map(|session| (session.id, session.recovered)).collect::<Vec<_>>(), vec![(stale_id, true)]);
+        assert_eq!(database.get_open_work_session().unwrap(), Some(kept_id));
+        
+        // Once reviewed, a session is no longer stale
+        database.close_stale_session(&sessions[0], SessionOutcome::Interrupted, false).unwrap();
+        assert!(database.get_stale_sessions(Some(kept_id)).unwrap().is_empty());
+    }
 }
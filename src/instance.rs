@@ -0,0 +1,77 @@
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum InstanceError {
+    #[error("Another timer is already running on {} (pid {pid}). Use `attach` to control it or pass a different --database", db.display())]
+    AlreadyRunning { db: PathBuf, pid: String },
+
+    #[error("Failed to lock {}: {source}", path.display())]
+    Io { path: PathBuf, source: io::Error },
+}
+
+/// Path of the lock file guarding a database
+pub fn lock_path(db_path: &Path) -> PathBuf {
+    sibling_path(db_path, "lock")
+}
+
+/// Path of the control socket served by the timer that owns a database
+pub fn socket_path(db_path: &Path) -> PathBuf {
+    sibling_path(db_path, "sock")
+}
+
+// Append a suffix to the full file name so `a.db` and `a.sqlite` don't share files
+fn sibling_path(db_path: &Path, suffix: &str) -> PathBuf {
+    let mut name = db_path.as_os_str().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Exclusive lock ensuring only one timer runs per database.
+///
+/// The lock is an OS file lock, so it is released automatically if the process dies.
+/// The file itself is left in place; deleting it could let two processes lock different inodes.
+pub struct InstanceLock {
+    _file: File,
+}
+
+impl InstanceLock {
+    pub fn acquire(db_path: &Path) -> Result<Self, InstanceError> {
+        let path = lock_path(db_path);
+        let io_error = |source| InstanceError::Io { path: path.clone(), source };
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .map_err(io_error)?;
+
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                // The owner writes its pid into the file; show it to help the user find it
+                let mut pid = String::new();
+                let _ = file.read_to_string(&mut pid);
+                let pid = pid.trim();
+                return Err(InstanceError::AlreadyRunning {
+                    db: db_path.to_path_buf(),
+                    pid: if pid.is_empty() { "unknown".to_string() } else { pid.to_string() },
+                });
+            }
+            Err(TryLockError::Error(e)) => return Err(io_error(e)),
+        }
+
+        file.set_len(0).map_err(io_error)?;
+        file.seek(SeekFrom::Start(0)).map_err(io_error)?;
+        write!(file, "{}", std::process::id()).map_err(io_error)?;
+        file.flush().map_err(io_error)?;
+
+        Ok(Self { _file: file })
+    }
+}
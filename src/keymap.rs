@@ -0,0 +1,488 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use thiserror::Error;
+
+/// Something a key in the interactive view can do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Start,
+    Pause,
+    Toggle,
+    Next,
+    Quit,
+    AddNote,
+    SwitchTask,
+    InternalInterruption,
+    ExternalInterruption,
+    Extend,
+    Abandon,
+    Stats,
+    NextTab,
+    PrevTab,
+    ScrollDown,
+    ScrollUp,
+    PageDown,
+    PageUp,
+    Top,
+    Bottom,
+    Help,
+}
+
+impl Action {
+    pub const ALL: [Action; 21] = [
+        Action::Start,
+        Action::Pause,
+        Action::Toggle,
+        Action::Next,
+        Action::Quit,
+        Action::AddNote,
+        Action::SwitchTask,
+        Action::InternalInterruption,
+        Action::ExternalInterruption,
+        Action::Extend,
+        Action::Abandon,
+        Action::Stats,
+        Action::NextTab,
+        Action::PrevTab,
+        Action::ScrollDown,
+        Action::ScrollUp,
+        Action::PageDown,
+        Action::PageUp,
+        Action::Top,
+        Action::Bottom,
+        Action::Help,
+    ];
+
+    /// Name used in the key bindings file
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Start => "start",
+            Action::Pause => "pause",
+            Action::Toggle => "toggle",
+            Action::Next => "next",
+            Action::Quit => "quit",
+            Action::AddNote => "add_note",
+            Action::SwitchTask => "switch_task",
+            Action::InternalInterruption => "internal_interruption",
+            Action::ExternalInterruption => "external_interruption",
+            Action::Extend => "extend",
+            Action::Abandon => "abandon",
+            Action::Stats => "stats",
+            Action::NextTab => "next_tab",
+            Action::PrevTab => "prev_tab",
+            Action::ScrollDown => "scroll_down",
+            Action::ScrollUp => "scroll_up",
+            Action::PageDown => "page_down",
+            Action::PageUp => "page_up",
+            Action::Top => "top",
+            Action::Bottom => "bottom",
+            Action::Help => "help",
+        }
+    }
+
+    /// Short description for the help overlay
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::Start => "Start/resume",
+            Action::Pause => "Pause, or cancel an auto-start",
+            Action::Toggle => "Pause or resume",
+            Action::Next => "Next interval",
+            Action::Quit => "Quit",
+            Action::AddNote => "Add a note",
+            Action::SwitchTask => "Switch task",
+            Action::InternalInterruption => "Log an internal interruption",
+            Action::ExternalInterruption => "Log an external interruption",
+            Action::Extend => "Extend the current interval",
+            Action::Abandon => "Abandon the work session",
+            Action::Stats => "Stats / timer",
+            Action::NextTab => "Next tab",
+            Action::PrevTab => "Previous tab",
+            Action::ScrollDown => "Scroll down",
+            Action::ScrollUp => "Scroll up",
+            Action::PageDown => "Page down",
+            Action::PageUp => "Page up",
+            Action::Top => "Scroll to top",
+            Action::Bottom => "Scroll to bottom",
+            Action::Help => "Show this help",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Action::ALL.iter().copied().find(|action| action.name() == name)
+    }
+
+    fn default_keys(&self) -> &'static [&'static str] {
+        match self {
+            Action::Start => &["s"],
+            Action::Pause => &["p"],
+            Action::Toggle => &["space"],
+            Action::Next => &["n"],
+            Action::Quit => &["q", "esc"],
+            Action::AddNote => &["a"],
+            Action::SwitchTask => &["t"],
+            Action::InternalInterruption => &["'"],
+            Action::ExternalInterruption => &["-"],
+            Action::Extend => &["+"],
+            Action::Abandon => &["x"],
+            Action::Stats => &["o"],
+            Action::NextTab => &["tab", "l"],
+            Action::PrevTab => &["shift-tab", "h"],
+            Action::ScrollDown => &["j", "down"],
+            Action::ScrollUp => &["k", "up"],
+            Action::PageDown => &["pgdn", "ctrl-d"],
+            Action::PageUp => &["pgup", "ctrl-u"],
+            Action::Top => &["g", "home"],
+            Action::Bottom => &["G", "end"],
+            Action::Help => &["?"],
+        }
+    }
+}
+
+/// A key together with its modifiers, e.g. `ctrl-d`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        // Shift is already part of characters ('G') and of BackTab, and terminals
+        // disagree on whether they report it as well
+        let modifiers = match code {
+            KeyCode::Char(_) | KeyCode::BackTab => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Self { code, modifiers }
+    }
+
+    pub fn from_event(event: &KeyEvent) -> Self {
+        Self::new(event.code, event.modifiers)
+    }
+
+    /// Parse chords like `s`, `G`, `space`, `ctrl-d` or `shift-tab`. `#` and `,` are
+    /// written `hash` and `comma`, since the bindings file uses them as separators.
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut key = text;
+
+        // A trailing '-' is the minus key itself, not a separator
+        while let Some((modifier, rest)) = key.split_once('-').filter(|(_, rest)| !rest.is_empty()) {
+            modifiers |= match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return None,
+            };
+            key = rest;
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(ch), None) if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::Char(ch.to_ascii_uppercase()),
+            (Some(ch), None) => KeyCode::Char(ch),
+            _ => match key.to_ascii_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "hash" => KeyCode::Char('#'),
+                "comma" => KeyCode::Char(','),
+                "tab" if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "esc" | "escape" => KeyCode::Esc,
+                "enter" | "return" => KeyCode::Enter,
+                "backspace" => KeyCode::Backspace,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "pgup" | "pageup" => KeyCode::PageUp,
+                "pgdn" | "pagedown" => KeyCode::PageDown,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "insert" => KeyCode::Insert,
+                "delete" | "del" => KeyCode::Delete,
+                name => {
+                    let number = name.strip_prefix('f')?.parse().ok()?;
+                    KeyCode::F(number)
+                }
+            },
+        };
+
+        Some(Self::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "alt-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "shift-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "space"),
+            KeyCode::Char('#') => write!(f, "hash"),
+            KeyCode::Char(',') => write!(f, "comma"),
+            KeyCode::Char(ch) => write!(f, "{}", ch),
+            KeyCode::Tab => write!(f, "tab"),
+            KeyCode::BackTab => write!(f, "shift-tab"),
+            KeyCode::Esc => write!(f, "esc"),
+            KeyCode::Enter => write!(f, "enter"),
+            KeyCode::Backspace => write!(f, "backspace"),
+            KeyCode::Up => write!(f, "up"),
+            KeyCode::Down => write!(f, "down"),
+            KeyCode::Left => write!(f, "left"),
+            KeyCode::Right => write!(f, "right"),
+            KeyCode::PageUp => write!(f, "pgup"),
+            KeyCode::PageDown => write!(f, "pgdn"),
+            KeyCode::Home => write!(f, "home"),
+            KeyCode::End => write!(f, "end"),
+            KeyCode::Insert => write!(f, "insert"),
+            KeyCode::Delete => write!(f, "delete"),
+            KeyCode::F(number) => write!(f, "f{}", number),
+            _ => write!(f, "?"),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum KeymapError {
+    #[error("Failed to read {}: {source}", path.display())]
+    Io { path: PathBuf, source: io::Error },
+
+    #[error("{}:{line}: {message}", path.display())]
+    Syntax { path: PathBuf, line: usize, message: String },
+
+    #[error("`{chord}` is bound to both {first} and {second}")]
+    Conflict { chord: String, first: &'static str, second: &'static str },
+
+    #[error("`{chord}` is reserved and can't be bound to {action}")]
+    Reserved { chord: String, action: &'static str },
+}
+
+/// Directory for this program's configuration files
+pub fn config_dir() -> Option<PathBuf> {
+    let config_home = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    };
+    Some(config_home.join(env!("CARGO_PKG_NAME")))
+}
+
+/// Key bindings file read when `--keymap` isn't given
+pub fn default_path() -> Option<PathBuf> {
+    Some(config_dir()?.join("keys.conf"))
+}
+
+/// Chords with fixed meanings: Ctrl-C always quits (raw mode swallows SIGINT)
+/// and the digits select tabs
+fn is_reserved(chord: &KeyChord) -> bool {
+    chord.modifiers.is_empty() && matches!(chord.code, KeyCode::Char('1'..='9'))
+        || *chord == KeyChord::new(KeyCode::Char('c'), KeyModifiers::CONTROL)
+}
+
+/// Maps key chords to actions
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: HashMap<Action, Vec<KeyChord>>,
+    actions: HashMap<KeyChord, Action>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = Action::ALL
+            .iter()
+            .map(|action| {
+                let keys = action.default_keys().iter().filter_map(|key| KeyChord::parse(key)).collect();
+                (*action, keys)
+            })
+            .collect();
+        Self::from_bindings(bindings).expect("default key bindings are conflict-free")
+    }
+}
+
+impl Keymap {
+    fn from_bindings(bindings: HashMap<Action, Vec<KeyChord>>) -> Result<Self, KeymapError> {
+        let mut actions = HashMap::new();
+        // Check in a fixed order so the same file always reports the same conflict
+        for action in Action::ALL {
+            for chord in bindings.get(&action).into_iter().flatten() {
+                if is_reserved(chord) {
+                    return Err(KeymapError::Reserved { chord: chord.to_string(), action: action.name() });
+                }
+                if let Some(first) = actions.insert(*chord, action) {
+                    if first != action {
+                        return Err(KeymapError::Conflict {
+                            chord: chord.to_string(),
+                            first: first.name(),
+                            second: action.name(),
+                        });
+                    }
+                }
+            }
+        }
+        Ok(Self { bindings, actions })
+    }
+
+    /// Load bindings from `path`, or from the default file if it exists. Each line of the
+    /// file is `action = chord, chord...` and replaces the defaults for that action only;
+    /// an empty list unbinds it. `#` starts a comment.
+    pub fn load(path: Option<&Path>) -> Result<Self, KeymapError> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => match default_path().filter(|path| path.exists()) {
+                Some(path) => path,
+                None => return Ok(Self::default()),
+            },
+        };
+
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|source| KeymapError::Io { path: path.clone(), source })?;
+        Self::parse(&path, &contents)
+    }
+
+    fn parse(path: &Path, contents: &str) -> Result<Self, KeymapError> {
+        let mut bindings = Self::default().bindings;
+
+        for (index, line) in contents.lines().enumerate() {
+            let syntax_error = |message: String| KeymapError::Syntax {
+                path: path.to_path_buf(),
+                line: index + 1,
+                message,
+            };
+
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (name, keys) = line
+                .split_once('=')
+                .ok_or_else(|| syntax_error("expected `action = key, ...`".to_string()))?;
+            let action = Action::from_name(name.trim())
+                .ok_or_else(|| syntax_error(format!("unknown action `{}`", name.trim())))?;
+
+            let keys: Vec<&str> = keys.split(',').map(str::trim).collect();
+            // An empty list unbinds the action, but an empty key among others is most
+            // likely a literal comma
+            if keys.len() > 1 && keys.contains(&"") {
+                return Err(syntax_error("empty key; write the comma key as `comma`".to_string()));
+            }
+            let chords = keys
+                .into_iter()
+                .filter(|key| !key.is_empty())
+                .map(|key| KeyChord::parse(key).ok_or_else(|| syntax_error(format!("unknown key `{}`", key))))
+                .collect::<Result<Vec<_>, _>>()?;
+            bindings.insert(action, chords);
+        }
+
+        Self::from_bindings(bindings)
+    }
+
+    pub fn action(&self, event: &KeyEvent) -> Option<Action> {
+        self.actions.get(&KeyChord::from_event(event)).copied()
+    }
+
+    pub fn keys(&self, action: Action) -> &[KeyChord] {
+        self.bindings.get(&action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// First key bound to `action`, for hints; `None` if it is unbound
+    pub fn hint(&self, action: Action) -> Option<String> {
+        self.keys(action).first().map(KeyChord::to_string)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(contents: &str) -> Result<Keymap, KeymapError> {
+        Keymap::parse(Path::new("keys.conf"), contents)
+    }
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn chords_round_trip_through_display() {
+        for text in ["s", "G", "'", "-", "space", "ctrl-d", "ctrl--", "alt-x", "ctrl-alt-up", "shift-tab", "pgdn", "f5", "hash", "comma"] {
+            let chord = KeyChord::parse(text).unwrap();
+            assert_eq!(chord.to_string(), text);
+            assert_eq!(KeyChord::parse(&chord.to_string()), Some(chord));
+        }
+    }
+
+    #[test]
+    fn chords_accept_aliases_and_shift() {
+        assert_eq!(KeyChord::parse("shift-g"), KeyChord::parse("G"));
+        assert_eq!(KeyChord::parse("CTRL-d"), KeyChord::parse("ctrl-d"));
+        assert_eq!(KeyChord::parse("pagedown"), KeyChord::parse("pgdn"));
+        assert_eq!(KeyChord::parse("backtab"), KeyChord::parse("shift-tab"));
+        assert_eq!(KeyChord::parse("hyper-x"), None);
+        assert_eq!(KeyChord::parse("f"), Some(KeyChord::new(KeyCode::Char('f'), KeyModifiers::NONE)));
+        assert_eq!(KeyChord::parse("fx"), None);
+        assert_eq!(KeyChord::parse(""), None);
+        // Terminals may or may not report shift along with an uppercase letter
+        let event = press(KeyCode::Char('G'), KeyModifiers::SHIFT);
+        assert_eq!(Keymap::default().action(&event), Some(Action::Bottom));
+    }
+
+    #[test]
+    fn bindings_replace_the_defaults_of_their_action() {
+        let keymap = parse("# comment\ntoggle = space, ctrl-p  # trailing\nadd_note =\nextend = hash, comma\n").unwrap();
+        assert_eq!(keymap.action(&press(KeyCode::Char('p'), KeyModifiers::CONTROL)), Some(Action::Toggle));
+        assert_eq!(keymap.action(&press(KeyCode::Char('a'), KeyModifiers::NONE)), None);
+        assert_eq!(keymap.action(&press(KeyCode::Char('+'), KeyModifiers::NONE)), None);
+        assert_eq!(keymap.action(&press(KeyCode::Char(','), KeyModifiers::NONE)), Some(Action::Extend));
+        assert_eq!(keymap.hint(Action::Extend).as_deref(), Some("hash"));
+        assert_eq!(keymap.hint(Action::AddNote), None);
+        assert_eq!(keymap.hint(Action::Next).as_deref(), Some("n"));
+    }
+
+    #[test]
+    fn conflicting_bindings_are_rejected() {
+        match parse("next = s") {
+            Err(KeymapError::Conflict { chord, first, second }) => {
+                assert_eq!((chord.as_str(), first, second), ("s", "start", "next"));
+            }
+            other => panic!("expected a conflict, got {:?}", other),
+        }
+        // Binding a key twice to the same action is fine
+        assert!(parse("next = n, n").is_ok());
+    }
+
+    #[test]
+    fn reserved_keys_cannot_be_bound() {
+        for (line, chord, action) in [("help = 5", "5", "help"), ("quit = q, ctrl-c", "ctrl-c", "quit")] {
+            match parse(line) {
+                Err(KeymapError::Reserved { chord: reserved, action: bound }) => {
+                    assert_eq!((reserved.as_str(), bound), (chord, action));
+                }
+                other => panic!("expected {} to be reserved, got {:?}", line, other),
+            }
+        }
+        assert!(parse("help = 0, alt-1, ctrl-x").is_ok());
+    }
+
+    #[test]
+    fn syntax_errors_name_the_line() {
+        for (contents, message) in [
+            ("\nstart s", "keys.conf:2: expected `action = key, ...`"),
+            ("begin = s", "keys.conf:1: unknown action `begin`"),
+            ("start = s, hyper-s", "keys.conf:1: unknown key `hyper-s`"),
+            ("extend = +, ,", "keys.conf:1: empty key; write the comma key as `comma`"),
+        ] {
+            assert_eq!(parse(contents).unwrap_err().to_string(), message);
+        }
+    }
+}
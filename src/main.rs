@@ -1,87 +1,245 @@
+use std::ffi::OsString;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::Duration as StdDuration;
 use std::io::{self, Write};
 
-use chrono::Duration;
-use clap::Parser;
+use chrono::{DateTime, Duration, Local};
+use clap::{Parser, ValueEnum};
 use crossterm::{
     cursor,
-    event::{self, Event, KeyCode, KeyEvent},
+    event::{self, Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind},
     execute,
-    style::{self, Color, Stylize},
-    terminal::{self, Clear, ClearType},
+    terminal,
 };
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
 
 mod cli;
+mod control;
 mod db;
+mod instance;
+mod keymap;
+#[cfg(feature = "mqtt")]
+mod mqtt;
 mod notification;
 mod pomodoro;
+mod service;
+mod signals;
 mod sound;
 mod stats_chart;
+mod theme;
+mod tui;
 
-use cli::{Args, Command};
-use db::Database;
+use cli::{Args, Command, ServiceAction};
+use control::{ControlClient, ControlError, ControlServer};
+use db::{Database, DatabaseError, NoteTriage, SessionOutcome};
+use instance::InstanceLock;
+use keymap::{Action, Keymap};
 use notification::get_sound_notifier;
-use pomodoro::{Pomodoro, PomodoroCommand, PomodoroConfig, PomodoroState};
-use stats_chart::{display_session_chart, display_daily_chart, display_type_chart};
+use pomodoro::{InterruptionKind, Interval, Pomodoro, PomodoroCommand, PomodoroConfig, PomodoroError, PomodoroState, PomodoroStatus, TimerMode};
+use stats_chart::{display_session_chart, display_daily_chart, display_interruption_chart, display_type_chart};
+use theme::Theme;
+use tui::{extension_summary_text, title_text, Prompt, PromptKind, Renderer, StatsData, Tab, Target, View};
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
+async fn main() {
     let args = Args::parse();
     
+    // Print errors with Display rather than the Debug output `main` would use
+    if let Err(e) = run(args).await {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn run(args: Args) -> Result<(), Box<dyn std::error::Error>> {
     // Initialize database
     let database = Arc::new(Database::new(args.database.to_str().unwrap_or("pomodoro.db"))?);
     
     // Check if a command was specified
     match args.command {
         Some(Command::Start) | Some(Command::Stop) | Some(Command::Next) | None => {
-            // Initialize notifier with sound support based on user preference
-            let notifier = get_sound_notifier(args.sound);
+            // Forward the command to the timer that owns this database, if there is one
+            if let Some(command) = remote_command(args.command.as_ref()) {
+                if let Some(mut client) = connect_to_timer(&args).await? {
+                    let status = client.send(&command).await?;
+                    print_command_result(&command);
+                    print_status(&status);
+                    return Ok(());
+                }
+            }
             
-            // Create Pomodoro config
-            let config = PomodoroConfig {
-                work_duration: Duration::minutes(args.pomodoro_minutes as i64),
-                short_break_duration: Duration::minutes(args.short_break_minutes as i64),
-                long_break_duration: Duration::minutes(args.long_break_minutes as i64),
-                long_break_after: args.pomodoros_until_long_break,
+            // A reachable timer (possibly socket-activated by systemd) already owns the database
+            if args.command.is_none()
+                && ControlClient::connect(&instance::socket_path(&args.database)).await.is_ok()
+            {
+                return Err(format!(
+                    "A timer is already running for {}. Use `attach` to control it",
+                    args.database.display()
+                )
+                .into());
+            }
+            
+            // Check the key bindings and theme before taking over the timer
+            let (keymap, theme) = match args.command {
+                Some(Command::Start) | None => {
+                    (Keymap::load(args.keymap.as_deref())?, Theme::load(args.theme.as_deref(), args.ascii)?)
+                }
+                _ => (Keymap::default(), Theme::default()),
             };
             
-            // Create Pomodoro instance
-            let pomodoro = Arc::new(Mutex::new(Pomodoro::new(
-                config,
-                database.clone(),
-                notifier,
-            )));
+            // From here on this process owns the timer; fails clearly if another one does
+            let lock = InstanceLock::acquire(&args.database)?;
+            let (log_tx, mut log_rx) = mpsc::unbounded_channel();
+            let pomodoro = create_pomodoro(&args, &database, log_tx.clone())?;
             
             match args.command {
                 Some(Command::Start) => {
-                    // Start the timer without interactive mode
-                    let mut pom = pomodoro.lock().unwrap();
-                    pom.start()?;
-                    drop(pom);
+                    // Start the timer, then hand over to interactive mode
+                    pomodoro.lock().unwrap().start()?;
                     
-                    run_interactive_mode(pomodoro, database.clone()).await?;
+                    run_interactive_mode(pomodoro, database.clone(), &args, &keymap, theme, lock, (log_tx, log_rx)).await?;
                 }
                 Some(Command::Stop) => {
                     let mut pom = pomodoro.lock().unwrap();
                     pom.stop()?;
-                    println!("Pomodoro timer stopped.");
+                    database.save_timer_state(&pom.snapshot())?;
+                    print_command_result(&PomodoroCommand::Stop);
+                    print_logged(&mut log_rx);
                 }
                 Some(Command::Next) => {
                     let mut pom = pomodoro.lock().unwrap();
                     pom.next()?;
-                    println!("Moved to next Pomodoro/break interval.");
+                    database.save_timer_state(&pom.snapshot())?;
+                    print_command_result(&PomodoroCommand::Next);
+                    print_logged(&mut log_rx);
                 }
                 None => {
                     // If no command specified, start the interactive mode
-                    run_interactive_mode(pomodoro, database.clone()).await?;
+                    run_interactive_mode(pomodoro, database.clone(), &args, &keymap, theme, lock, (log_tx, log_rx)).await?;
                 }
                 _ => unreachable!(), // This case is already filtered by the match guard
             }
         }
+        Some(Command::Status) => {
+            match connect_to_timer(&args).await? {
+                Some(mut client) => print_status(&client.status().await?),
+                None => println!("No timer is running for {}.", args.database.display()),
+            }
+        }
+        Some(Command::Attach) => {
+            run_attach_mode(&args, database.clone()).await?;
+        }
+        Some(Command::Rpc) => {
+            // Editor plugins expect a timer to talk to, so always start one if needed
+            let args = Args { auto_spawn: true, ..args };
+            let client = connect_to_timer(&args).await?.ok_or("No timer is running")?;
+            client.proxy_stdio().await?;
+        }
+        Some(Command::Daemon) => {
+            let lock = InstanceLock::acquire(&args.database)?;
+            let (log_tx, log_rx) = mpsc::unbounded_channel();
+            print_log(log_rx);
+            let pomodoro = create_pomodoro(&args, &database, log_tx.clone())?;
+            
+            run_daemon(pomodoro, &args, lock, log_tx).await?;
+        }
+        Some(Command::Service { action }) => {
+            match action {
+                ServiceAction::Install => {
+                    // The unit runs from the user's home, so bake in absolute paths
+                    let database = std::path::absolute(&args.database)?;
+                    service::install(
+                        &std::env::current_exe()?,
+                        &daemon_args(&args, &database),
+                        &instance::socket_path(&database),
+                    )?;
+                }
+                ServiceAction::Uninstall => service::uninstall()?,
+                ServiceAction::Status => service::status()?,
+            }
+        }
+        Some(Command::Note { ref text, list, dismiss, promote }) => {
+            if list {
+                let notes = database.get_open_notes()?;
+                if notes.is_empty() {
+                    println!("No notes to triage.");
+                }
+                for note in notes {
+                    println!("{:>4}  {}  {}", note.id, note.created_at.format("%Y-%m-%d %H:%M"), note.text);
+                }
+            } else if let Some(id) = dismiss {
+                if !database.triage_note(id, NoteTriage::Dismissed)? {
+                    return Err(format!("No note with id {}", id).into());
+                }
+                println!("Note {} dismissed.", id);
+            } else if let Some(id) = promote {
+                let note = database
+                    .get_open_notes()?
+                    .into_iter()
+                    .find(|note| note.id == id)
+                    .ok_or_else(|| format!("No open note with id {}", id))?;
+                let mut client = connect_to_timer(&args)
+                    .await?
+                    .ok_or_else(|| format!("No timer is running for {}.", args.database.display()))?;
+                let status = client.status().await?;
+                client
+                    .send(&PomodoroCommand::SetTask { project: status.project, task: Some(note.text.clone()) })
+                    .await?;
+                database.triage_note(id, NoteTriage::Task)?;
+                println!("Task set to: {}", note.text);
+            } else {
+                // The running timer knows which work session the note belongs to
+                let text = text.join(" ");
+                match connect_to_timer(&args).await? {
+                    Some(mut client) => {
+                        client.send(&PomodoroCommand::AddNote { text }).await?;
+                        println!("Note saved.");
+                    }
+                    None => {
+                        let id = database.add_note(&text, None)?;
+                        println!("Note {} saved.", id);
+                    }
+                }
+            }
+        }
+        Some(Command::Interrupt { kind, ref reason }) => {
+            let mut client = connect_to_timer(&args)
+                .await?
+                .ok_or_else(|| format!("No timer is running for {}.", args.database.display()))?;
+            let reason = Some(reason.join(" ")).filter(|reason| !reason.is_empty());
+            client.send(&PomodoroCommand::Interrupt { kind, reason }).await?;
+            println!("{} interruption logged.", kind.label());
+        }
+        Some(Command::Extend { minutes }) => {
+            let mut client = connect_to_timer(&args)
+                .await?
+                .ok_or_else(|| format!("No timer is running for {}.", args.database.display()))?;
+            let status = client.send(&PomodoroCommand::Extend { minutes }).await?;
+            println!("{}.", describe_extension(&status));
+        }
+        Some(Command::Abandon { ref confirmation }) => {
+            let mut client = connect_to_timer(&args)
+                .await?
+                .ok_or_else(|| format!("No timer is running for {}.", args.database.display()))?;
+            let confirmation = Some(confirmation.join(" ")).filter(|text| !text.is_empty());
+            client.send(&PomodoroCommand::Abandon { confirmation }).await?;
+            println!("Work session abandoned.");
+        }
+        Some(Command::Repair { yes }) => {
+            // A running timer's own session isn't stale, nor a strict one kept open to resume
+            let live_session_id = match ControlClient::connect(&instance::socket_path(&args.database)).await {
+                Ok(mut client) => client.status().await?.session_id,
+                Err(_) => database.load_timer_state()?.and_then(|snapshot| snapshot.session_id),
+            };
+            repair_sessions(&database, live_session_id, yes)?;
+        }
         Some(Command::Stats { limit, days, display, chart }) => {
             // Handle stats command without initializing notifier
+            let theme = Theme::load(args.theme.as_deref(), args.ascii)?;
             match display.as_str() {
                 "sessions" => {
                     let sessions = database.get_session_stats(limit)?;
@@ -93,22 +251,44 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                         println!("No sessions recorded yet.");
                     } else {
                         for (i, session) in sessions.iter().enumerate() {
-                            let status = if session.completed { "✅ Completed" } else { "❌ Cancelled" };
+                            let status = match (session.outcome, args.ascii) {
+                                (None, _) => "In progress".to_string(),
+                                (Some(outcome), true) => outcome.label().to_string(),
+                                (Some(SessionOutcome::Completed), false) => "✅ Completed".to_string(),
+                                (Some(outcome), false) => format!("❌ {}", outcome.label()),
+                            };
                             let duration_min = session.duration_seconds / 60;
                             let session_id = session.id.unwrap_or(0);
                             let end_time_str = match session.end_time {
                                 Some(time) => time.format("%Y-%m-%d %H:%M").to_string(),
                                 None => "In progress".to_string(),
                             };
+                            // Focused time within the wall-clock span, once the session has ended
+                            let focus_str = match (session.focus_seconds, session.end_time) {
+                                (Some(focus), Some(end)) => format!(
+                                    " - Focused: {} of {} min, {}",
+                                    focus / 60,
+                                    (end - session.start_time).num_minutes(),
+                                    plural(session.pause_count, "pause"),
+                                ),
+                                _ => String::new(),
+                            };
+                            let overtime_str = if session.overtime_seconds > 0 {
+                                format!(" - Overtime: {}", format_duration(session.overtime_seconds))
+                            } else {
+                                String::new()
+                            };
                             
                             println!(
-                                "{}. ID: {} - {} ({} min) - Started: {} - Ended: {} - {}",
+                                "{}. ID: {} - {} ({} min) - Started: {} - Ended: {}{}{} - {}",
                                 i + 1,
                                 session_id,
                                 session.session_type,
                                 duration_min,
                                 session.start_time.format("%Y-%m-%d %H:%M"),
                                 end_time_str,
+                                focus_str,
+                                overtime_str,
                                 status,
                             );
                         }
@@ -116,7 +296,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     
                     // Display a basic chart if requested
                     if chart {
-                        display_session_chart(&sessions)?;
+                        display_session_chart(&sessions, &theme)?;
                     }
                 },
                 "daily" => {
@@ -129,16 +309,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                         println!("No data for the selected period.");
                     } else {
                         // Print header
-                        println!("{:<12} {:>12} {:>12} {:>12} {:>15}", 
-                            "Date", "Work Sessions", "Minutes", "Completed", "Completion Rate");
-                        println!("{}", "-".repeat(65));
+                        println!("{:<12} {:>12} {:>12} {:>12} {:>12} {:>15}", 
+                            "Date", "Work Sessions", "Focus Min", "Overtime Min", "Completed", "Completion Rate");
+                        println!("{}", "-".repeat(78));
                         
                         // Print rows
                         for stat in &daily_stats {
-                            println!("{:<12} {:>12} {:>12} {:>12} {:>14.1}%",
+                            println!("{:<12} {:>12} {:>12} {:>12} {:>12} {:>14.1}%",
                                 stat.date, 
                                 stat.work_sessions,
                                 stat.total_work_minutes,
+                                stat.overtime_minutes,
                                 stat.completed_work_sessions,
                                 stat.completion_rate * 100.0
                             );
@@ -146,7 +327,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                         
                         // Display a chart if requested
                         if chart {
-                            display_daily_chart(&daily_stats)?;
+                            display_daily_chart(&daily_stats, &theme)?;
                         }
                     }
                 },
@@ -157,12 +338,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     println!("---------------------------");
                     
                     println!("Total work sessions:     {}", summary.total_work_sessions);
-                    println!("Total work minutes:      {}", summary.total_work_minutes);
+                    println!("Total focus minutes:     {}", summary.total_work_minutes);
+                    println!("Overtime minutes:        {}", summary.overtime_minutes);
                     println!("Completed sessions:      {}", summary.completed_sessions);
                     println!("Completion rate:         {:.1}%", summary.completion_rate * 100.0);
                     println!("Avg sessions per day:    {:.1}", summary.avg_sessions_per_day);
                     println!("Longest streak:          {} days", summary.longest_streak_days);
                     println!("Current streak:          {} days", summary.current_streak_days);
+                    for extension in database.get_extension_stats()? {
+                        println!("{}", extension_summary_text(&extension));
+                    }
                 },
                 "types" => {
                     let type_stats = database.get_session_type_stats()?;
@@ -190,12 +375,62 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                         
                         // Display a chart if requested
                         if chart {
-                            display_type_chart(&type_stats)?;
+                            display_type_chart(&type_stats, &theme)?;
+                        }
+                    }
+                },
+                "interruptions" => {
+                    let daily_stats = database.get_interruption_daily_stats(days)?;
+                    let hourly_stats = database.get_interruption_hourly_stats(days)?;
+                    
+                    println!("Interruptions (Last {} days):", days);
+                    println!("--------------------------{}", "-".repeat(days.to_string().len()));
+                    
+                    if daily_stats.is_empty() {
+                        println!("No data for the selected period.");
+                    } else {
+                        let pomodoros: i64 = daily_stats.iter().map(|stat| stat.work_sessions).sum();
+                        let internal: i64 = daily_stats.iter().map(|stat| stat.internal).sum();
+                        let external: i64 = daily_stats.iter().map(|stat| stat.external).sum();
+                        
+                        println!("Work sessions:           {}", pomodoros);
+                        println!("Internal interruptions:  {} ({:.1} per pomodoro)", internal, per_pomodoro(internal, pomodoros));
+                        println!("External interruptions:  {} ({:.1} per pomodoro)", external, per_pomodoro(external, pomodoros));
+                        println!();
+                        
+                        // Print header
+                        println!("{:<12} {:>13} {:>10} {:>10} {:>13}",
+                            "Date", "Work Sessions", "Internal", "External", "Per Pomodoro");
+                        println!("{}", "-".repeat(62));
+                        
+                        // Print rows
+                        for stat in &daily_stats {
+                            println!("{:<12} {:>13} {:>10} {:>10} {:>13.1}",
+                                stat.date,
+                                stat.work_sessions,
+                                stat.internal,
+                                stat.external,
+                                per_pomodoro(stat.internal + stat.external, stat.work_sessions)
+                            );
+                        }
+                    }
+                    
+                    if !hourly_stats.is_empty() {
+                        println!();
+                        println!("{:<12} {:>10} {:>10}", "Hour", "Internal", "External");
+                        println!("{}", "-".repeat(34));
+                        for stat in &hourly_stats {
+                            println!("{:<12} {:>10} {:>10}", format!("{:02}:00", stat.hour), stat.internal, stat.external);
+                        }
+                        
+                        // Display a chart if requested
+                        if chart {
+                            display_interruption_chart(&hourly_stats, &theme)?;
                         }
                     }
                 },
                 _ => {
-                    println!("Unknown display type '{}'. Valid options are: sessions, daily, summary, types", display);
+                    println!("Unknown display type '{}'. Valid options are: sessions, daily, summary, types, interruptions", display);
                 }
             }
         }
@@ -204,285 +439,1365 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-async fn run_interactive_mode(
-    pomodoro: Arc<Mutex<Pomodoro>>,
-    database: Arc<Database>,
-) -> Result<(), Box<dyn std::error::Error>> {
-    // Set up command channel
-    let (cmd_tx, cmd_rx) = mpsc::channel(32);
+/// Set up the timer from the command line. Problems it runs into later are reported on `log_tx`.
+fn create_pomodoro(
+    args: &Args,
+    database: &Arc<Database>,
+    log_tx: mpsc::UnboundedSender<String>,
+) -> Result<Arc<Mutex<Pomodoro>>, Box<dyn std::error::Error>> {
+    // Starting from idle always begins with work
+    if args.sequence.first().is_some_and(|interval| interval.state != PomodoroState::Work) {
+        return Err("--sequence must start with a work interval".into());
+    }
     
-    // Spawn the Pomodoro timer task
-    let timer_pomodoro = pomodoro.clone();
-    let timer_handle = tokio::spawn(async move {
-        pomodoro::run_pomodoro_timer(timer_pomodoro, cmd_rx).await;
-    });
+    // Initialize notifier with sound support based on user preference
+    let notifier = get_sound_notifier(args.sound, args.notifier, args.ascii, log_tx.clone());
     
-    // Set up terminal
-    terminal::enable_raw_mode()?;
-    execute!(io::stdout(), cursor::Hide, Clear(ClearType::All))?;
+    // Create Pomodoro config
+    let config = PomodoroConfig {
+        work_duration: Duration::minutes(args.pomodoro_minutes as i64),
+        short_break_duration: Duration::minutes(args.short_break_minutes as i64),
+        long_break_duration: Duration::minutes(args.long_break_minutes as i64),
+        long_break_after: args.pomodoros_until_long_break,
+        mode: if args.flowtime {
+            TimerMode::Flowtime { break_fraction: args.break_fraction }
+        } else if !args.sequence.is_empty() {
+            TimerMode::Sequence(args.sequence.clone())
+        } else {
+            TimerMode::Fixed
+        },
+        auto_start: args.auto_start,
+        auto_start_grace: Duration::seconds(args.auto_start_grace as i64),
+        overtime: args.overtime,
+        overtime_reminder: Duration::minutes(args.overtime_reminder as i64),
+        extend_by: Duration::minutes(args.extend_minutes as i64),
+        max_extensions: args.max_extensions,
+        strict_phrase: args.strict.then(|| args.strict_phrase.clone()),
+    };
     
-    let mut last_state = None;
-    let mut last_seconds = None;
-    let mut redraw_counter = 0;
+    // The caller holds the instance lock, so any open session was left by a timer that died,
+    // except a strict work session the last run kept open to resume
+    let snapshot = database.load_timer_state()?;
+    let kept_session_id = snapshot.as_ref().and_then(|snapshot| snapshot.session_id);
+    let recovered = database.recover_stale_sessions(kept_session_id)?;
+    if recovered > 0 {
+        eprintln!(
+            "Closed {} left open by a timer that stopped unexpectedly. Run `{} repair` to review.",
+            plural(recovered as i64, "session"),
+            env!("CARGO_PKG_NAME"),
+        );
+    }
     
-    // Main loop for interactive mode
-    loop {
-        // Only redraw at most once per 250ms (about 4 frames per second)
-        // to reduce flickering
-        let current_state;
-        let current_seconds;
-        
-        {
-            let pom = pomodoro.lock().unwrap();
-            current_state = pom.get_state();
-            current_seconds = pom.get_remaining_seconds();
+    let mut pomodoro = Pomodoro::new(config, database.clone(), notifier, log_tx);
+    pomodoro.set_task(args.task.clone());
+    
+    // Pick up where the last run left off
+    if let Some(mut snapshot) = snapshot {
+        // A later run may have finished the kept session and then crashed
+        if snapshot.session_id.is_some() && database.get_open_work_session()? != snapshot.session_id {
+            snapshot.session_id = None;
+        }
+        pomodoro.restore(&snapshot);
+    }
+    
+    Ok(Arc::new(Mutex::new(pomodoro)))
+}
+
+/// Walk through the stale sessions, asking how each one ended unless `accept` is set
+fn repair_sessions(database: &Database, live_session_id: Option<i64>, accept: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let sessions = database.get_stale_sessions(live_session_id)?;
+    if sessions.is_empty() {
+        println!("No sessions need repair.");
+        return Ok(());
+    }
+    
+    let mut lines = io::stdin().lines();
+    for session in &sessions {
+        println!(
+            "Session {}: {}, started {}, planned {} min, {}",
+            session.id,
+            session.session_type,
+            session.start_time.format("%Y-%m-%d %H:%M"),
+            session.duration_seconds / 60,
+            plural(session.pause_count, "pause"),
+        );
+        if session.recovered {
+            println!("  Closed automatically as {} at {}", session.outcome.as_str(), session.end_time.format("%H:%M"));
+        } else {
+            println!("  Still open; suggested: {} at {}", session.outcome.as_str(), session.end_time.format("%H:%M"));
         }
         
-        // Check if we need to redraw - only redraw when:
-        // 1. State changes
-        // 2. Seconds change (but only if not paused)
-        // 3. Or once every 4 cycles (for any other updates)
-        // For paused or idle state, we only need to redraw much less frequently
-        // since nothing is changing
-        let is_static = current_state == PomodoroState::Paused || current_state == PomodoroState::Idle;
-        let periodic_refresh = if is_static {
-            redraw_counter % 20 == 0  // Much less frequent updates when paused/idle
+        let outcome = if accept {
+            Some(session.outcome)
         } else {
-            redraw_counter % 4 == 0   // Regular updates when running
+            loop {
+                print!("  [c]ompleted, [s]kipped, [i]nterrupted, [a]bandoned, Enter to accept, q to stop: ");
+                io::stdout().flush()?;
+                
+                let Some(line) = lines.next().transpose()? else {
+                    println!();
+                    return Ok(());
+                };
+                match line.trim() {
+                    "" => break Some(session.outcome),
+                    "c" => break Some(SessionOutcome::Completed),
+                    "s" => break Some(SessionOutcome::Skipped),
+                    "i" => break Some(SessionOutcome::Interrupted),
+                    "a" => break Some(SessionOutcome::Abandoned),
+                    "q" => break None,
+                    other => println!("  Unknown answer '{}'", other),
+                }
+            }
         };
         
-        let should_redraw = last_state != Some(current_state) || 
-                          (!is_static && last_seconds != Some(current_seconds)) || 
-                          periodic_refresh;
-                          
-        if should_redraw {
-            // Draw the UI
-            draw_ui(&pomodoro, &database)?;
-            last_state = Some(current_state);
-            last_seconds = Some(current_seconds);
+        let Some(outcome) = outcome else { break };
+        database.close_stale_session(session, outcome, false)?;
+        println!("  Recorded as {}.", outcome.as_str());
+    }
+    
+    Ok(())
+}
+
+/// Average count per work session, zero when there were none
+fn per_pomodoro(count: i64, work_sessions: i64) -> f64 {
+    if work_sessions > 0 {
+        count as f64 / work_sessions as f64
+    } else {
+        0.0
+    }
+}
+
+/// Timer commands that can be sent to a timer running in another process
+fn remote_command(command: Option<&Command>) -> Option<PomodoroCommand> {
+    match command {
+        Some(Command::Start) => Some(PomodoroCommand::start()),
+        Some(Command::Stop) => Some(PomodoroCommand::Stop),
+        Some(Command::Next) => Some(PomodoroCommand::Next),
+        _ => None,
+    }
+}
+
+fn print_command_result(command: &PomodoroCommand) {
+    match command {
+        PomodoroCommand::Start { .. } => println!("Pomodoro timer started."),
+        PomodoroCommand::Stop => println!("Pomodoro timer stopped."),
+        PomodoroCommand::Next => println!("Moved to next Pomodoro/break interval."),
+        _ => {}
+    }
+}
+
+fn print_status(status: &PomodoroStatus) {
+    let minutes = status.remaining_seconds / 60;
+    let seconds = status.remaining_seconds % 60;
+    let label = status.interval_label();
+    
+    match (status.state, status.prev_state) {
+        (PomodoroState::Idle, _) => println!("State: Idle"),
+        (PomodoroState::Paused, _) if status.is_overtime() => {
+            println!("State: Paused ({}, overtime, {:02}:{:02} over)", label, -minutes, -seconds)
+        }
+        (_, _) if status.is_overtime() => {
+            println!("State: {} (overtime, {:02}:{:02} over)", label, -minutes, -seconds)
+        }
+        (PomodoroState::Paused, Some(_)) => match status.auto_start_in {
+            Some(grace) => {
+                println!("State: Paused ({}, starts in {}; stop to cancel)", label, plural(grace, "second"))
+            }
+            None if status.is_open_ended() => {
+                println!("State: Paused ({}, flowtime, {:02}:{:02} worked)", label, -minutes, -seconds)
+            }
+            None => println!("State: Paused ({}, {:02}:{:02} remaining)", label, minutes, seconds),
+        },
+        (_, _) if status.is_open_ended() => {
+            println!("State: {} (flowtime, {:02}:{:02} worked)", label, -minutes, -seconds)
+        }
+        (_, _) => println!("State: {} ({:02}:{:02} remaining)", label, minutes, seconds),
+    }
+    println!("Completed pomodoros: {}", status.completed_pomodoros);
+    if let Some(sequence) = &status.sequence {
+        println!("Sequence: step {} of {}", sequence.step, sequence.steps);
+    }
+    if let Some(task) = &status.task {
+        println!("Task: {}", task);
+    }
+}
+
+/// Connect to the timer that owns the database, starting a background one first if
+/// `--auto-spawn` is set. Returns `None` when no timer is running and none was spawned.
+async fn connect_to_timer(args: &Args) -> Result<Option<ControlClient>, Box<dyn std::error::Error>> {
+    let socket = instance::socket_path(&args.database);
+    if let Ok(client) = ControlClient::connect(&socket).await {
+        return Ok(Some(client));
+    }
+    
+    if !args.auto_spawn {
+        return Ok(None);
+    }
+    
+    spawn_daemon(args)?;
+    
+    // Give the background timer a few seconds to bind its socket
+    for _ in 0..50 {
+        tokio::time::sleep(StdDuration::from_millis(100)).await;
+        if let Ok(client) = ControlClient::connect(&socket).await {
+            return Ok(Some(client));
         }
+    }
+    
+    Err(format!("Background timer did not start; try running `{} daemon` to see why", env!("CARGO_PKG_NAME")).into())
+}
+
+/// Arguments that run this binary as a `daemon` with the same timer settings
+fn daemon_args(args: &Args, database: &Path) -> Vec<OsString> {
+    let mut daemon_args: Vec<OsString> = vec![
+        "--database".into(),
+        database.as_os_str().to_os_string(),
+        "--pomodoro-minutes".into(),
+        args.pomodoro_minutes.to_string().into(),
+        "--short-break-minutes".into(),
+        args.short_break_minutes.to_string().into(),
+        "--long-break-minutes".into(),
+        args.long_break_minutes.to_string().into(),
+        "--pomodoros-until-long-break".into(),
+        args.pomodoros_until_long_break.to_string().into(),
+        "--auto-start-grace".into(),
+        args.auto_start_grace.to_string().into(),
+        "--overtime-reminder".into(),
+        args.overtime_reminder.to_string().into(),
+        "--extend-minutes".into(),
+        args.extend_minutes.to_string().into(),
+        "--break-fraction".into(),
+        args.break_fraction.to_string().into(),
+    ];
+    
+    if let Some(max) = args.max_extensions {
+        daemon_args.push("--max-extensions".into());
+        daemon_args.push(max.to_string().into());
+    }
+    if args.strict {
+        daemon_args.push("--strict".into());
+        daemon_args.push("--strict-phrase".into());
+        daemon_args.push((&args.strict_phrase).into());
+    }
+    
+    if args.overtime {
+        daemon_args.push("--overtime".into());
+    }
+    if args.flowtime {
+        daemon_args.push("--flowtime".into());
+    }
+    if !args.sequence.is_empty() {
+        let sequence: Vec<String> = args.sequence.iter().map(Interval::to_string).collect();
+        daemon_args.push("--sequence".into());
+        daemon_args.push(sequence.join(",").into());
+    }
+    
+    if let Some(auto_start) = args.auto_start.to_possible_value() {
+        daemon_args.push("--auto-start".into());
+        daemon_args.push(auto_start.get_name().into());
+    }
+    
+    if let Some(task) = &args.task {
+        daemon_args.push("--task".into());
+        daemon_args.push(task.into());
+    }
+    
+    if args.ascii {
+        daemon_args.push("--ascii".into());
+    }
+    if let Some(notifier) = args.notifier.to_possible_value() {
+        daemon_args.push("--notifier".into());
+        daemon_args.push(notifier.get_name().into());
+    }
+    
+    #[cfg(feature = "mqtt")]
+    if let Some(host) = &args.mqtt_host {
+        daemon_args.push("--mqtt-host".into());
+        daemon_args.push(host.into());
+        daemon_args.push("--mqtt-port".into());
+        daemon_args.push(args.mqtt_port.to_string().into());
+        daemon_args.push("--mqtt-topic-prefix".into());
+        daemon_args.push((&args.mqtt_topic_prefix).into());
+    }
+    
+    daemon_args.push("daemon".into());
+    daemon_args
+}
+
+/// Launch this binary as a detached `daemon` with the same timer settings
+fn spawn_daemon(args: &Args) -> io::Result<()> {
+    let mut command = std::process::Command::new(std::env::current_exe()?);
+    command
+        .args(daemon_args(args, &args.database))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    
+    // Keep the daemon out of our process group so Ctrl-C in this terminal doesn't reach it
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    
+    command.spawn()?;
+    Ok(())
+}
+
+/// Everything a process runs while it owns the timer: the timer task, integrations,
+/// signal handling and the control socket. Dropping it releases the instance lock.
+struct TimerHost {
+    cmd_tx: mpsc::Sender<PomodoroCommand>,
+    shutdown: Arc<AtomicBool>,
+    timer_handle: JoinHandle<()>,
+    _control: ControlServer,
+    _lock: InstanceLock,
+}
+
+impl TimerHost {
+    /// Problems the integrations run into, such as MQTT connection errors, are sent to `log_tx`
+    fn spawn(
+        pomodoro: &Arc<Mutex<Pomodoro>>,
+        args: &Args,
+        lock: InstanceLock,
+        log_tx: mpsc::UnboundedSender<String>,
+    ) -> io::Result<Self> {
+        // Set up command and status channels
+        let (cmd_tx, cmd_rx) = mpsc::channel(32);
+        let initial_status = pomodoro.lock().unwrap().status();
+        let (status_tx, status_rx) = watch::channel(initial_status);
         
-        redraw_counter += 1;
+        // Spawn the Pomodoro timer task
+        let timer_pomodoro = pomodoro.clone();
+        let timer_handle = tokio::spawn(async move {
+            pomodoro::run_pomodoro_timer(timer_pomodoro, cmd_rx, status_tx).await;
+        });
         
-        // Poll for keyboard events with timeout (250ms normally, longer when static)
-        let poll_timeout = if is_static {
-            StdDuration::from_millis(500)  // Longer timeout when paused/idle
-        } else {
-            StdDuration::from_millis(250)  // Normal timeout when running
-        };
+        spawn_integrations(args, status_rx.clone(), cmd_tx.clone(), log_tx);
         
-        if event::poll(poll_timeout)? {
-            if let Event::Key(KeyEvent {
-                code, modifiers: _, kind, state: _,
-            }) = event::read()?
-            {
-                if kind == event::KeyEventKind::Press {
-                    match code {
-                        KeyCode::Char('q') | KeyCode::Esc => {
-                            // Quit
-                            // Ensure that we properly stop any active session before shutting down
-            let mut pomodoro = pomodoro.lock().unwrap();
-            if pomodoro.get_state() != PomodoroState::Idle && pomodoro.get_state() != PomodoroState::Paused {
-                let _ = pomodoro.stop();
-            }
-            drop(pomodoro); // Release the lock before sending shutdown command
-            let _ = cmd_tx.send(PomodoroCommand::Shutdown).await;
-                            break;
-                        }
-                        KeyCode::Char('s') => {
-                            // Start/resume
-                            let _ = cmd_tx.send(PomodoroCommand::Start).await;
-                        }
-                        KeyCode::Char('p') => {
-                            // Pause
-                            let _ = cmd_tx.send(PomodoroCommand::Stop).await;
-                        }
-                        KeyCode::Char('n') => {
-                            // Next
-                            let _ = cmd_tx.send(PomodoroCommand::Next).await;
-                        }
-                        _ => {}
-                    }
-                }
-            }
+        // Signals only raise a flag; the owner notices it and exits through the normal path
+        let shutdown = Arc::new(AtomicBool::new(false));
+        signals::spawn_signal_handler(cmd_tx.clone(), shutdown.clone())?;
+        
+        let control = ControlServer::spawn(
+            &instance::socket_path(&args.database),
+            pomodoro.clone(),
+            status_rx,
+            shutdown.clone(),
+        )?;
+        
+        Ok(Self {
+            cmd_tx,
+            shutdown,
+            timer_handle,
+            _control: control,
+            _lock: lock,
+        })
+    }
+    
+    /// Close the in-progress session, persist the timer state and stop the timer task
+    async fn finish(self, pomodoro: &Arc<Mutex<Pomodoro>>) -> Result<(), PomodoroError> {
+        let result = pomodoro.lock().unwrap().shutdown();
+        let _ = self.cmd_tx.send(PomodoroCommand::Shutdown).await;
+        
+        // Wait for the timer task to finish
+        if let Err(e) = self.timer_handle.await {
+            eprintln!("Error waiting for timer task to complete: {}", e);
         }
+        
+        result
     }
+}
+
+async fn run_daemon(
+    pomodoro: Arc<Mutex<Pomodoro>>,
+    args: &Args,
+    lock: InstanceLock,
+    log_tx: mpsc::UnboundedSender<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let host = TimerHost::spawn(&pomodoro, args, lock, log_tx)?;
+    eprintln!(
+        "Pomodoro timer running for {} (pid {})",
+        args.database.display(),
+        std::process::id()
+    );
     
-    // Wait for the timer task to finish
-    if let Err(e) = timer_handle.await {
-        eprintln!("Error waiting for timer task to complete: {}", e);
+    while !host.shutdown.load(Ordering::SeqCst) {
+        tokio::time::sleep(StdDuration::from_millis(250)).await;
     }
     
-    // Clean up terminal
-    terminal::disable_raw_mode()?;
-    execute!(io::stdout(), cursor::Show)?;
+    host.finish(&pomodoro).await?;
+    eprintln!("Pomodoro timer stopped");
     
     Ok(())
 }
 
-fn draw_ui(pomodoro: &Arc<Mutex<Pomodoro>>, database: &Arc<Database>) -> io::Result<()> {
-    let mut stdout = io::stdout();
+/// Print problems reported by the timer and its integrations to stderr as they come, for
+/// the daemon, whose stderr goes to the journal
+fn print_log(mut log_rx: mpsc::UnboundedReceiver<String>) {
+    tokio::spawn(async move {
+        while let Some(message) = log_rx.recv().await {
+            eprintln!("{}", message);
+        }
+    });
+}
+
+/// Print the problems reported so far to stderr, once nothing is drawn over it
+fn print_logged(log_rx: &mut mpsc::UnboundedReceiver<String>) {
+    while let Ok(message) = log_rx.try_recv() {
+        eprintln!("{}", message);
+    }
+}
+
+/// Start optional integrations that observe the timer status and may send it commands
+fn spawn_integrations(
+    args: &Args,
+    status_rx: watch::Receiver<PomodoroStatus>,
+    cmd_tx: mpsc::Sender<PomodoroCommand>,
+    log_tx: mpsc::UnboundedSender<String>,
+) {
+    #[cfg(feature = "mqtt")]
+    if let Some(host) = args.mqtt_host.clone() {
+        let config = mqtt::MqttConfig {
+            host,
+            port: args.mqtt_port,
+            client_id: format!("pomodoro-cli-{}", std::process::id()),
+            topic_prefix: args.mqtt_topic_prefix.clone(),
+        };
+        tokio::spawn(async move {
+            if let Err(e) = mqtt::run_mqtt_bridge(config, status_rx, cmd_tx, log_tx.clone()).await {
+                let _ = log_tx.send(format!("MQTT integration stopped: {}", e));
+            }
+        });
+    }
     
-    let (state, remaining_seconds, completed_pomodoros) = {
-        let pom = pomodoro.lock().unwrap();
-        (
-            pom.get_state(),
-            pom.get_remaining_seconds(),
-            pom.get_completed_pomodoros(),
-        )
-    };
+    #[cfg(not(feature = "mqtt"))]
+    let _ = (args, status_rx, cmd_tx, log_tx);
+}
+
+async fn run_interactive_mode(
+    pomodoro: Arc<Mutex<Pomodoro>>,
+    database: Arc<Database>,
+    args: &Args,
+    keymap: &Keymap,
+    theme: Theme,
+    lock: InstanceLock,
+    (log_tx, mut log_rx): (mpsc::UnboundedSender<String>, mpsc::UnboundedReceiver<String>),
+) -> Result<(), Box<dyn std::error::Error>> {
+    let host = TimerHost::spawn(&pomodoro, args, lock, log_tx)?;
+    
+    // Set up terminal; the guard restores it on every exit path. Plain mode leaves it alone.
+    let auto_pause = args.auto_pause.map(StdDuration::from_secs);
+    let terminal_guard = if args.plain { None } else { Some(TerminalGuard::enter(auto_pause.is_some(), !args.no_mouse)?) };
     
-    // Format time
-    let minutes = remaining_seconds / 60;
-    let seconds = remaining_seconds % 60;
-    
-    // Get state information
-    let (state_text, state_color) = match state {
-        PomodoroState::Idle => ("Idle", Color::White),
-        PomodoroState::Work => ("Working", Color::Red),
-        PomodoroState::ShortBreak => ("Short Break", Color::Green),
-        PomodoroState::LongBreak => ("Long Break", Color::Blue),
-        PomodoroState::Paused => ("Paused", Color::Yellow),
+    let mut link = TimerLink::Local { pomodoro: &pomodoro, cmd_tx: &host.cmd_tx };
+    // Both views show reported problems themselves, since anything written to stderr
+    // would land on top of the interactive view
+    let result = if args.plain {
+        plain_loop(&mut link, &database, Some(&mut log_rx), &host.shutdown).await
+    } else {
+        interactive_loop(&mut link, &database, keymap, theme, auto_pause, Some(&mut log_rx), &host.shutdown).await
     };
     
-    // Only clear screen once at the beginning of the function
-    // to reduce flickering
-    execute!(
-        stdout,
-        cursor::MoveTo(0, 0),
-        Clear(ClearType::All)
-    )?;
-    
-    // Draw the header
-    execute!(
-        stdout,
-        cursor::MoveTo(0, 0),
-        style::PrintStyledContent(
-            "🍅 Pomodoro Timer".bold().with(Color::White)
-        )
-    )?;
-    
-    // Draw the state
-    execute!(
-        stdout,
-        cursor::MoveTo(0, 2),
-        style::PrintStyledContent(
-            format!("State: {}", state_text).with(state_color)
-        )
-    )?;
-    
-    // Draw the time remaining
-    let time_display = format!("{:02}:{:02}", minutes, seconds);
-    execute!(
-        stdout,
-        cursor::MoveTo(0, 4),
-        style::PrintStyledContent(
-            format!("Time Remaining: {}", time_display).bold().with(Color::White)
-        )
-    )?;
-    
-    // Draw completed pomodoros
-    execute!(
-        stdout,
-        cursor::MoveTo(0, 6),
-        style::PrintStyledContent(
-            format!("Completed Pomodoros: {}", completed_pomodoros).with(Color::White)
+    // Whatever ended the loop (quit key, signal or a terminal error), close the
+    // in-progress session and persist the timer state before exiting
+    let finish_result = host.finish(&pomodoro).await;
+    
+    // Clean up terminal
+    drop(terminal_guard);
+    print_logged(&mut log_rx);
+    
+    if let Err(e) = finish_result {
+        eprintln!("Failed to save timer state: {}", e);
+    }
+    
+    result
+}
+
+/// Mirror and control a timer owned by another process. Quitting only detaches.
+async fn run_attach_mode(
+    args: &Args,
+    database: Arc<Database>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let keymap = Keymap::load(args.keymap.as_deref())?;
+    let theme = Theme::load(args.theme.as_deref(), args.ascii)?;
+    let client = connect_to_timer(args).await?.ok_or_else(|| {
+        format!(
+            "No timer is running for {}. Start one first or pass --auto-spawn",
+            args.database.display()
         )
-    )?;
+    })?;
     
-    // Try to get and display today's stats with better error handling
-    if let Ok(daily_stats) = database.get_daily_stats(1) {
-        if !daily_stats.is_empty() {
-            let today = &daily_stats[0];
-            
-            execute!(
-                stdout,
-                cursor::MoveTo(0, 8),
-                style::PrintStyledContent(
-                    "Today's Progress:".bold().with(Color::White)
-                )
-            )?;
+    // Forward signal commands over their own connection so they don't interleave with the view's requests
+    let (cmd_tx, mut cmd_rx) = mpsc::channel(8);
+    let shutdown = Arc::new(AtomicBool::new(false));
+    signals::spawn_signal_handler(cmd_tx, shutdown.clone())?;
+    
+    let socket = instance::socket_path(&args.database);
+    tokio::spawn(async move {
+        while let Some(command) = cmd_rx.recv().await {
+            if let Ok(mut client) = ControlClient::connect(&socket).await {
+                let _ = client.send(&command).await;
+            }
+        }
+    });
+    
+    let auto_pause = args.auto_pause.map(StdDuration::from_secs);
+    let terminal_guard = if args.plain { None } else { Some(TerminalGuard::enter(auto_pause.is_some(), !args.no_mouse)?) };
+    
+    let mut link = TimerLink::Remote(client);
+    let result = if args.plain {
+        plain_loop(&mut link, &database, None, &shutdown).await
+    } else {
+        interactive_loop(&mut link, &database, &keymap, theme, auto_pause, None, &shutdown).await
+    };
+    
+    drop(terminal_guard);
+    
+    result
+}
+
+/// Where the interactive view reads the timer status from and sends commands to
+enum TimerLink<'a> {
+    Local {
+        pomodoro: &'a Arc<Mutex<Pomodoro>>,
+        cmd_tx: &'a mpsc::Sender<PomodoroCommand>,
+    },
+    Remote(ControlClient),
+}
+
+impl TimerLink<'_> {
+    async fn status(&mut self) -> Result<PomodoroStatus, ControlError> {
+        match self {
+            TimerLink::Local { pomodoro, .. } => Ok(pomodoro.lock().unwrap().status()),
+            TimerLink::Remote(client) => client.status().await,
+        }
+    }
+    
+    /// Send a command whose rejection doesn't matter to the user (e.g. setting the task), ignoring it
+    async fn send(&mut self, command: PomodoroCommand) -> Result<(), ControlError> {
+        match self {
+            TimerLink::Local { cmd_tx, .. } => {
+                let _ = cmd_tx.send(command).await;
+                Ok(())
+            }
+            TimerLink::Remote(client) => match client.send(&command).await {
+                Ok(_) | Err(ControlError::Remote { .. }) => Ok(()),
+                Err(e) => Err(e),
+            },
+        }
+    }
+    
+    /// Send a command whose outcome the user sees; rejections come back as `ControlError::Remote`
+    async fn request(&mut self, command: PomodoroCommand) -> Result<(), ControlError> {
+        match self {
+            TimerLink::Local { pomodoro, .. } => pomodoro.lock().unwrap().apply(&command).map_err(ControlError::rejected),
+            TimerLink::Remote(client) => client.send(&command).await.map(|_| ()),
+        }
+    }
+}
+
+/// Puts the terminal into raw mode and restores it when dropped, including on early returns
+struct TerminalGuard {
+    report_focus: bool,
+    capture_mouse: bool,
+}
+
+impl TerminalGuard {
+    /// `report_focus` asks the terminal to send focus gained/lost events and
+    /// `capture_mouse` to send clicks and scrolling
+    fn enter(report_focus: bool, capture_mouse: bool) -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        if report_focus {
+            execute!(io::stdout(), event::EnableFocusChange)?;
+        }
+        if capture_mouse {
+            execute!(io::stdout(), event::EnableMouseCapture)?;
+        }
+        // Save the window title on the terminal's title stack, since the view overwrites it
+        write!(io::stdout(), "\x1b[22;0t")?;
+        Ok(Self { report_focus, capture_mouse })
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = write!(io::stdout(), "\x1b[23;0t");
+        if self.capture_mouse {
+            let _ = execute!(io::stdout(), event::DisableMouseCapture);
+        }
+        if self.report_focus {
+            let _ = execute!(io::stdout(), event::DisableFocusChange);
+        }
+        let _ = terminal::disable_raw_mode();
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+    }
+}
+
+/// A stretch of time the terminal spent without focus, for `--auto-pause`
+struct Away {
+    since: DateTime<Local>,
+    // Set once the grace period has passed and the timer was acted on
+    action: Option<AwayAction>,
+}
+
+#[derive(Clone, Copy)]
+enum AwayAction {
+    /// A break was paused and resumes on return
+    PausedBreak(PomodoroState),
+    /// The work session kept running and is recorded as interrupted
+    Interrupted,
+}
+
+impl Away {
+    fn new() -> Self {
+        Self { since: Local::now(), action: None }
+    }
+    
+    /// Act on the timer once focus has been gone for longer than `grace`
+    async fn check(
+        &mut self,
+        grace: StdDuration,
+        status: &PomodoroStatus,
+        link: &mut TimerLink<'_>,
+    ) -> Result<(), ControlError> {
+        let away_for = (Local::now() - self.since).to_std().unwrap_or_default();
+        if self.action.is_some() || away_for < grace {
+            return Ok(());
+        }
+        
+        match status.state {
+            PomodoroState::ShortBreak | PomodoroState::LongBreak => {
+                link.send(PomodoroCommand::Stop).await?;
+                self.action = Some(AwayAction::PausedBreak(status.state));
+            }
+            PomodoroState::Work => self.action = Some(AwayAction::Interrupted),
+            PomodoroState::Idle | PomodoroState::Paused => {}
+        }
+        Ok(())
+    }
+    
+    /// Focus is back: record the occurrence, resume a paused break and say what happened
+    async fn finish(
+        self,
+        status: &PomodoroStatus,
+        link: &mut TimerLink<'_>,
+        database: &Database,
+        view: &mut View,
+    ) -> Result<(), ControlError> {
+        let Some(action) = self.action else {
+            return Ok(());
+        };
+        let away_for = format_duration((Local::now() - self.since).num_seconds());
+        
+        match action {
+            AwayAction::PausedBreak(state) => {
+                // Leave the timer alone if it was changed in the meantime (e.g. from another client)
+                if status.state == PomodoroState::Paused && status.prev_state == Some(state) {
+                    link.send(PomodoroCommand::start()).await?;
+                }
+                view.set_message(format!("Away for {}: {} paused and resumed", away_for, state.label()));
+            }
+            AwayAction::Interrupted => {
+                view.set_message(format!("Away for {}: work session recorded as interrupted", away_for));
+            }
+        }
+        
+        if let Err(e) = self.record(database) {
+            view.set_message(format!("Failed to record focus loss: {}", e));
+        }
+        Ok(())
+    }
+    
+    /// Save the occurrence if the grace period ran out, ending it now
+    fn record(&self, database: &Database) -> Result<(), DatabaseError> {
+        let (session_type, action) = match self.action {
+            Some(AwayAction::PausedBreak(state)) => (state, "paused"),
+            Some(AwayAction::Interrupted) => (PomodoroState::Work, "interrupted"),
+            None => return Ok(()),
+        };
+        database.record_focus_loss(session_type.as_str(), action, self.since, Local::now())?;
+        Ok(())
+    }
+}
+
+/// `95` as "1m 35s"
+fn format_duration(seconds: i64) -> String {
+    if seconds < 60 {
+        format!("{}s", seconds)
+    } else {
+        format!("{}m {:02}s", seconds / 60, seconds % 60)
+    }
+}
+
+async fn interactive_loop(
+    link: &mut TimerLink<'_>,
+    database: &Arc<Database>,
+    keymap: &Keymap,
+    theme: Theme,
+    auto_pause: Option<StdDuration>,
+    mut log_rx: Option<&mut mpsc::UnboundedReceiver<String>>,
+    shutdown: &AtomicBool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut stdout = io::stdout();
+    let mut renderer = Renderer::new();
+    let mut view = View::new(keymap, theme);
+    let mut last_status: Option<PomodoroStatus> = None;
+    let mut stats = StatsData::default();
+    let mut away: Option<Away> = None;
+    let mut title = String::new();
+    
+    // Main loop for interactive mode
+    while !shutdown.load(Ordering::SeqCst) {
+        let status = link.status().await?;
+        
+        // The stats only move when an interval ends or the timer changes state.
+        // Transitions also trigger notifications, which may print over the view.
+        if last_status.as_ref().is_none_or(|last| status.is_transition_from(last)) {
+            stats = StatsData::load(database);
+            renderer.invalidate();
+        }
+        
+        // Review what was noted during work once the break comes round
+        if status.is_break() && last_status.as_ref().is_some_and(|last| !last.is_break()) {
+            view.open_triage(database.get_open_notes().unwrap_or_default());
+        }
+        
+        if let (Some(away), Some(grace)) = (&mut away, auto_pause) {
+            away.check(grace, &status, link).await?;
+        }
+        
+        // Reported problems (e.g. an unreachable MQTT broker) show on the message line
+        if let Some(log_rx) = log_rx.as_deref_mut() {
+            while let Ok(message) = log_rx.try_recv() {
+                view.set_message(message);
+            }
+        }
+        
+        // Show the countdown in the terminal (or tmux) title too
+        let new_title = title_text(&status);
+        if new_title != title {
+            execute!(stdout, terminal::SetTitle(&new_title))?;
+            title = new_title;
+        }
+        
+        // Frames are diffed against the previous one, so redrawing every pass is cheap
+        let (width, height) = terminal::size()?;
+        renderer.render(&mut stdout, view.frame(width, height, &status, &stats))?;
+        
+        // Poll for keyboard events with timeout
+        if event::poll(StdDuration::from_millis(250))? {
+            let event = event::read()?;
             
-            execute!(
-                stdout,
-                cursor::MoveTo(0, 9),
-                style::PrintStyledContent(
-                    format!(" Work sessions: {}/{}", today.completed_work_sessions, today.work_sessions)
-                        .with(Color::White)
-                )
-            )?;
+            // The terminal may have reflowed or cleared what was on screen
+            if let Event::Resize(..) = event {
+                renderer.invalidate();
+                last_status = Some(status);
+                continue;
+            }
             
-            execute!(
-                stdout,
-                cursor::MoveTo(0, 10),
-                style::PrintStyledContent(
-                    format!(" Total work minutes: {}", today.total_work_minutes)
-                        .with(Color::White)
-                )
-            )?;
+            // Only reported when auto-pause asked the terminal for focus events
+            if let Event::FocusLost = event {
+                away.get_or_insert_with(Away::new);
+            }
+            if let Event::FocusGained = event {
+                if let Some(away) = away.take() {
+                    away.finish(&status, link, database, &mut view).await?;
+                }
+            }
             
-            let completion_percent = (today.completion_rate * 100.0).round() as i64;
-            execute!(
-                stdout,
-                cursor::MoveTo(0, 11),
-                style::PrintStyledContent(
-                    format!(" Completion rate: {}%", completion_percent)
-                        .with(Color::White)
-                )
-            )?;
+            let tab = view.tab();
+            let keep_running = match event {
+                Event::Key(key) if key.kind == event::KeyEventKind::Press => {
+                    handle_key(key, &status, link, database, keymap, &mut view).await?
+                }
+                Event::Mouse(mouse) => handle_mouse(mouse, &status, link, &mut view).await?,
+                _ => true,
+            };
+            if !keep_running {
+                break;
+            }
+            if view.tab() != tab {
+                stats = StatsData::load(database);
+            }
+        }
+        
+        last_status = Some(status);
+    }
+    
+    // Quitting while away still counts
+    if let Some(away) = away {
+        away.record(database)?;
+    }
+    
+    Ok(())
+}
+
+const PLAIN_HELP: &str = "Commands: start (s), pause (p), toggle, next (n), status or an empty line, \
+task <name> (task alone clears it), note <text>, notes, use <note id>, dismiss <note id>, \
+internal (') or external (-) followed by an optional reason to log an interruption, \
+extend (+) followed by optional minutes, abandon followed by the phrase in strict mode, help (?), quit (q)";
+
+/// Line-based interactive mode for screen readers. Nothing is ever redrawn: state
+/// changes and each minute of the countdown are announced on a line of their own,
+/// and commands are typed as lines.
+async fn plain_loop(
+    link: &mut TimerLink<'_>,
+    database: &Arc<Database>,
+    mut log_rx: Option<&mut mpsc::UnboundedReceiver<String>>,
+    shutdown: &AtomicBool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut lines = spawn_line_reader();
+    let mut last_status: Option<PomodoroStatus> = None;
+    
+    println!("{}", PLAIN_HELP);
+    
+    while !shutdown.load(Ordering::SeqCst) {
+        let status = link.status().await?;
+        
+        if status.is_break() && last_status.as_ref().is_some_and(|last| !last.is_break()) {
+            print_open_notes(database, false);
+        }
+        
+        match &last_status {
+            None => println!("{}", describe_status(&status)),
+            Some(last) if status.state != last.state || status.prev_state != last.prev_state => {
+                println!("{}", describe_status(&status));
+            }
+            Some(last) if status.is_transition_from(last) => {
+                if status.completed_pomodoros != last.completed_pomodoros
+                    || status.auto_start_in.is_some() != last.auto_start_in.is_some()
+                    || status.is_overtime() != last.is_overtime()
+                {
+                    println!("{}", describe_status(&status));
+                } else {
+                    println!("{}", describe_task(&status));
+                }
+            }
+            Some(last) => {
+                // Announce each whole minute as the countdown reaches it, or as a flowtime session counts up to it
+                let minutes = minutes_left(&status);
+                if is_running(status.state) && minutes > 0 && minutes < minutes_left(last) {
+                    println!("{} left in {}", plural(minutes, "minute"), session_name(&status));
+                } else if status.is_open_ended() && status.remaining_seconds / 60 < last.remaining_seconds / 60 {
+                    println!("{} into the work session", counted_up_text(&status));
+                }
+            }
+        }
+        // Reported problems get a line of their own, like everything else
+        if let Some(log_rx) = log_rx.as_deref_mut() {
+            while let Ok(message) = log_rx.try_recv() {
+                println!("{}", message);
+            }
+        }
+        io::stdout().flush()?;
+        
+        let line = tokio::select! {
+            line = lines.recv() => line,
+            _ = tokio::time::sleep(StdDuration::from_millis(250)) => {
+                last_status = Some(status);
+                continue;
+            }
+        };
+        
+        // End of input quits, like the quit command
+        let Some(line) = line else { break };
+        let line = line.trim();
+        let (command, rest) = line.split_once(' ').unwrap_or((line, ""));
+        let rest = rest.trim();
+        
+        match command {
+            "q" | "quit" | "exit" => break,
+            "s" | "start" | "resume" | "p" | "pause" | "stop" | "toggle" | "n" | "next" | "skip" => {
+                let command = match command {
+                    "s" | "start" | "resume" => PomodoroCommand::start(),
+                    "toggle" => PomodoroCommand::Toggle,
+                    "n" | "next" | "skip" => PomodoroCommand::Next,
+                    _ => PomodoroCommand::Stop,
+                };
+                if let Some(refusal) = send_checked(link, command).await? {
+                    println!("{}", refusal);
+                }
+            }
+            "abandon" => {
+                let confirmation = Some(rest.to_string()).filter(|text| !text.is_empty());
+                println!("{}", abandon_session(link, confirmation).await?);
+            }
+            "" | "status" => println!("{}", describe_status(&status)),
+            "task" => {
+                link.send(PomodoroCommand::SetTask {
+                    project: status.project.clone(),
+                    task: Some(rest.to_string()),
+                })
+                .await?
+            }
+            "note" if rest.is_empty() => println!("Usage: note <text>"),
+            "note" => println!("{}", save_note(link, rest).await?),
+            "notes" => print_open_notes(database, true),
+            "'" | "internal" | "-" | "external" => {
+                let kind = match command {
+                    "'" | "internal" => InterruptionKind::Internal,
+                    _ => InterruptionKind::External,
+                };
+                println!("{}", log_interruption(link, kind, rest).await?);
+            }
+            "+" | "extend" if rest.is_empty() => println!("{}", extend_interval(link, None).await?),
+            "+" | "extend" => match rest.parse::<u32>() {
+                Ok(minutes) if minutes > 0 => println!("{}", extend_interval(link, Some(minutes)).await?),
+                _ => println!("'{}' is not a number of minutes", rest),
+            },
+            "use" | "dismiss" => {
+                let note = rest.parse::<i64>().ok().and_then(|id| {
+                    database.get_open_notes().ok()?.into_iter().find(|note| note.id == id)
+                });
+                match note {
+                    None => println!("No open note with id '{}'. Type notes to list them", rest),
+                    Some(note) if command == "use" => {
+                        link.send(PomodoroCommand::SetTask {
+                            project: status.project.clone(),
+                            task: Some(note.text.clone()),
+                        })
+                        .await?;
+                        let _ = database.triage_note(note.id, NoteTriage::Task);
+                    }
+                    Some(note) => {
+                        let _ = database.triage_note(note.id, NoteTriage::Dismissed);
+                        println!("Note {} dismissed", note.id);
+                    }
+                }
+            }
+            "?" | "help" => println!("{}", PLAIN_HELP),
+            _ => println!("Unknown command '{}'. Type help for the list of commands", command),
         }
+        
+        last_status = Some(status);
     }
     
-    // Row adjustment based on whether we displayed stats
-    let row_offset = if database.get_daily_stats(1).map(|s| !s.is_empty()).unwrap_or(false) {
-        13 // After the stats
+    Ok(())
+}
+
+/// List the notes waiting for triage, for plain mode
+fn print_open_notes(database: &Database, report_none: bool) {
+    let notes = database.get_open_notes().unwrap_or_default();
+    if notes.is_empty() {
+        if report_none {
+            println!("No notes to review");
+        }
+        return;
+    }
+    println!("{} to review. Type use <id> to make one the task, or dismiss <id>:", plural(notes.len() as i64, "note"));
+    for note in notes {
+        println!("{}: {}", note.id, note.text);
+    }
+}
+
+/// Read stdin lines on a plain thread; a pending read on tokio's stdin would keep
+/// the runtime from shutting down until the user pressed enter
+fn spawn_line_reader() -> mpsc::UnboundedReceiver<String> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        for line in io::stdin().lines().map_while(Result::ok) {
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+fn is_running(state: PomodoroState) -> bool {
+    matches!(state, PomodoroState::Work | PomodoroState::ShortBreak | PomodoroState::LongBreak)
+}
+
+/// Whole minutes left, rounded up so a fresh 25 minute session reads as 25
+fn minutes_left(status: &PomodoroStatus) -> i64 {
+    (status.remaining_seconds.max(0) + 59) / 60
+}
+
+fn plural(count: i64, unit: &str) -> String {
+    if count == 1 {
+        format!("{} {}", count, unit)
     } else {
-        8  // Original position
+        format!("{} {}s", count, unit)
+    }
+}
+
+/// Lowercase name of the current interval, or the one that resumes when paused, for use in a sentence
+fn session_name(status: &PomodoroStatus) -> String {
+    let state = match (status.state, status.prev_state) {
+        (PomodoroState::Paused, Some(state)) | (state, _) => state,
     };
+    match (&status.sequence, state) {
+        (Some(sequence), _) if sequence.name != state.as_str() => format!("{} interval", sequence.label.to_lowercase()),
+        (_, PomodoroState::ShortBreak) => "short break".to_string(),
+        (_, PomodoroState::LongBreak) => "long break".to_string(),
+        _ => "work session".to_string(),
+    }
+}
+
+/// How far a work session has counted up past zero, in overtime or flowtime, for plain mode
+fn counted_up_text(status: &PomodoroStatus) -> String {
+    let counted = -status.remaining_seconds;
+    if counted >= 60 {
+        plural(counted / 60, "minute")
+    } else {
+        plural(counted.max(0), "second")
+    }
+}
+
+/// One sentence describing the timer, for plain mode
+fn describe_status(status: &PomodoroStatus) -> String {
+    let time = if status.remaining_seconds >= 60 {
+        plural(minutes_left(status), "minute")
+    } else {
+        plural(status.remaining_seconds.max(0), "second")
+    };
+    let mut text = match (status.state, status.prev_state) {
+        (PomodoroState::Idle, _) => "Timer idle. Type start to begin".to_string(),
+        (PomodoroState::Paused, _) if status.is_overtime() => {
+            format!("Paused work session in overtime, {} over", counted_up_text(status))
+        }
+        (_, _) if status.is_overtime() => {
+            format!("Time's up, in overtime for {}. Type next to take your break", counted_up_text(status))
+        }
+        (PomodoroState::Paused, Some(_)) => match status.auto_start_in {
+            Some(grace) => format!(
+                "Starting {} in {}. Type pause to cancel",
+                session_name(status),
+                plural(grace, "second")
+            ),
+            None if status.is_open_ended() => {
+                format!("Paused flowtime work session, {} worked", counted_up_text(status))
+            }
+            None => format!("Paused {} with {} left", session_name(status), time),
+        },
+        (PomodoroState::Paused, None) => "Paused".to_string(),
+        (_, _) if status.is_open_ended() => format!(
+            "Working for {} in flowtime. Type next when you're ready for a break",
+            counted_up_text(status)
+        ),
+        (_, _) => format!("{}, {} left", status.interval_label(), time),
+    };
+    if let Some(sequence) = &status.sequence {
+        text.push_str(&format!(". Step {} of {} in the sequence", sequence.step, sequence.steps));
+    } else if status.break_fraction.is_some() {
+        // Flowtime has no long breaks, so there's no cycle to count through
+        text.push_str(&format!(". {} completed", plural(status.completed_pomodoros as i64, "work session")));
+    } else {
+        text.push_str(&format!(
+            ". {} of {} pomodoros until a long break",
+            status.completed_pomodoros % status.long_break_after.max(1),
+            status.long_break_after
+        ));
+    }
+    if status.task.is_some() || status.project.is_some() {
+        text.push_str(". ");
+        text.push_str(&describe_task(status));
+    }
+    text
+}
+
+fn describe_task(status: &PomodoroStatus) -> String {
+    match (&status.project, &status.task) {
+        (Some(project), Some(task)) => format!("Task: {} in {}", task, project),
+        (Some(project), None) => format!("Project: {}", project),
+        (None, Some(task)) => format!("Task: {}", task),
+        (None, None) => "Task cleared".to_string(),
+    }
+}
+
+/// React to a key press in the interactive view. Returns `false` when the user quits.
+async fn handle_key(
+    key: KeyEvent,
+    status: &PomodoroStatus,
+    link: &mut TimerLink<'_>,
+    database: &Database,
+    keymap: &Keymap,
+    view: &mut View,
+) -> Result<bool, ControlError> {
+    // Raw mode swallows SIGINT, so treat Ctrl-C as quit ourselves, whatever the key bindings say
+    if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+        return Ok(false);
+    }
     
-    // Draw controls
-    execute!(
-        stdout,
-        cursor::MoveTo(0, row_offset),
-        style::PrintStyledContent(
-            "Controls:".bold().with(Color::White)
-        )
-    )?;
+    // The triage list takes keys until it's closed
+    if view.triage_mut().is_some() {
+        handle_triage_key(key, status, link, database, view).await?;
+        return Ok(true);
+    }
     
-    execute!(
-        stdout,
-        cursor::MoveTo(0, row_offset + 1),
-        style::PrintStyledContent(
-            " s - Start/Resume".with(Color::White)
-        )
-    )?;
+    // While the prompt is open, keys edit its text
+    if let Some(prompt) = view.prompt_mut() {
+        let modified = key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+        match key.code {
+            KeyCode::Char('u') if modified => prompt.input.clear(),
+            KeyCode::Char(ch) if !modified => prompt.input.push(ch),
+            KeyCode::Backspace => {
+                prompt.input.pop();
+            }
+            KeyCode::Esc => {
+                view.close_prompt();
+            }
+            KeyCode::Enter => {
+                if let Some(prompt) = view.close_prompt() {
+                    submit_prompt(prompt, status, link, view).await?;
+                }
+            }
+            _ => {}
+        }
+        return Ok(true);
+    }
     
-    execute!(
-        stdout,
-        cursor::MoveTo(0, row_offset + 2),
-        style::PrintStyledContent(
-            " p - Pause".with(Color::White)
-        )
-    )?;
+    // Any key closes the help overlay
+    if view.help_visible() {
+        view.set_help(false);
+        return Ok(true);
+    }
+    view.clear_message();
     
-    execute!(
-        stdout,
-        cursor::MoveTo(0, row_offset + 3),
-        style::PrintStyledContent(
-            " n - Next".with(Color::White)
-        )
-    )?;
+    if let KeyCode::Char(digit) = key.code {
+        if let Some(tab) = Tab::from_digit(digit) {
+            view.select(tab);
+            return Ok(true);
+        }
+    }
     
-    execute!(
-        stdout,
-        cursor::MoveTo(0, row_offset + 4),
-        style::PrintStyledContent(
-            " q - Quit".with(Color::White)
-        )
-    )?;
+    match keymap.action(&key) {
+        Some(action) => perform(action, status, link, view).await,
+        None => Ok(true),
+    }
+}
+
+/// Keys for the list of notes up for triage
+async fn handle_triage_key(
+    key: KeyEvent,
+    status: &PomodoroStatus,
+    link: &mut TimerLink<'_>,
+    database: &Database,
+    view: &mut View,
+) -> Result<(), ControlError> {
+    let Some(triage) = view.triage_mut() else {
+        return Ok(());
+    };
     
-    // Make sure to flush the output to display immediately
-    stdout.flush()?;
+    let (note, outcome) = match key.code {
+        KeyCode::Up | KeyCode::Char('k') => {
+            triage.move_by(-1);
+            return Ok(());
+        }
+        KeyCode::Down | KeyCode::Char('j') => {
+            triage.move_by(1);
+            return Ok(());
+        }
+        KeyCode::Enter | KeyCode::Char('t') => (triage.take_selected(), NoteTriage::Task),
+        KeyCode::Char('d') | KeyCode::Delete => (triage.take_selected(), NoteTriage::Dismissed),
+        KeyCode::Esc | KeyCode::Char('q') => {
+            view.close_triage();
+            return Ok(());
+        }
+        _ => return Ok(()),
+    };
+    if triage.is_empty() {
+        view.close_triage();
+    }
+    let Some(note) = note else {
+        return Ok(());
+    };
+    
+    if outcome == NoteTriage::Task {
+        link.send(PomodoroCommand::SetTask { project: status.project.clone(), task: Some(note.text.clone()) })
+            .await?;
+        view.set_message(format!("Task switched to: {}", note.text));
+    }
+    if let Err(e) = database.triage_note(note.id, outcome) {
+        view.set_message(format!("Failed to update note: {}", e));
+    }
+    Ok(())
+}
+
+// Lines moved by one notch of the mouse wheel
+const WHEEL_SCROLL_LINES: isize = 3;
+
+/// React to a click or the mouse wheel in the interactive view. Returns `false` when the user quits.
+async fn handle_mouse(
+    mouse: MouseEvent,
+    status: &PomodoroStatus,
+    link: &mut TimerLink<'_>,
+    view: &mut View,
+) -> Result<bool, ControlError> {
+    match mouse.kind {
+        MouseEventKind::ScrollDown => view.scroll_by(WHEEL_SCROLL_LINES),
+        MouseEventKind::ScrollUp => view.scroll_by(-WHEEL_SCROLL_LINES),
+        MouseEventKind::Down(MouseButton::Left) => {
+            // The prompt and triage list only take keys; a click anywhere closes the help overlay
+            if view.prompt_mut().is_some() || view.triage_mut().is_some() {
+                return Ok(true);
+            }
+            if view.help_visible() {
+                view.set_help(false);
+                return Ok(true);
+            }
+            view.clear_message();
+            
+            match view.target_at(mouse.column, mouse.row) {
+                Some(Target::Action(action)) => return perform(action, status, link, view).await,
+                Some(Target::Tab(tab)) => view.select(tab),
+                None => {}
+            }
+        }
+        _ => {}
+    }
+    
+    Ok(true)
+}
+
+/// Carry out an action chosen by key or click. Returns `false` when the user quits.
+async fn perform(
+    action: Action,
+    status: &PomodoroStatus,
+    link: &mut TimerLink<'_>,
+    view: &mut View,
+) -> Result<bool, ControlError> {
+    match action {
+        Action::Quit => return Ok(false),
+        Action::Start | Action::Pause | Action::Toggle | Action::Next => {
+            let command = match action {
+                Action::Start => PomodoroCommand::start(),
+                Action::Pause => PomodoroCommand::Stop,
+                Action::Toggle => PomodoroCommand::Toggle,
+                _ => PomodoroCommand::Next,
+            };
+            if let Some(refusal) = send_checked(link, command).await? {
+                view.set_message(refusal);
+            }
+        }
+        Action::AddNote => view.open_prompt(PromptKind::Note, String::new()),
+        Action::SwitchTask => view.open_prompt(PromptKind::Task, status.task.clone().unwrap_or_default()),
+        Action::InternalInterruption => open_interruption_prompt(InterruptionKind::Internal, status, view),
+        Action::ExternalInterruption => open_interruption_prompt(InterruptionKind::External, status, view),
+        Action::Extend => view.set_message(extend_interval(link, None).await?),
+        Action::Abandon => open_abandon_prompt(status, view),
+        Action::Stats => view.toggle_stats(),
+        Action::NextTab => view.select(view.tab().next()),
+        Action::PrevTab => view.select(view.tab().previous()),
+        Action::ScrollDown => view.scroll_by(1),
+        Action::ScrollUp => view.scroll_by(-1),
+        Action::PageDown => view.scroll_by(10),
+        Action::PageUp => view.scroll_by(-10),
+        Action::Top => view.scroll_to(true),
+        Action::Bottom => view.scroll_to(false),
+        Action::Help => view.set_help(true),
+    }
     
+    Ok(true)
+}
+
+async fn submit_prompt(
+    prompt: Prompt,
+    status: &PomodoroStatus,
+    link: &mut TimerLink<'_>,
+    view: &mut View,
+) -> Result<(), ControlError> {
+    let input = prompt.input.trim().to_string();
+    match prompt.kind {
+        PromptKind::Task => {
+            let cleared = input.is_empty();
+            link.send(PomodoroCommand::SetTask {
+                project: status.project.clone(),
+                task: Some(input),
+            })
+            .await?;
+            view.set_message(if cleared { "Task cleared" } else { "Task switched" });
+        }
+        PromptKind::Note if input.is_empty() => {}
+        PromptKind::Note => view.set_message(save_note(link, &input).await?),
+        PromptKind::Interruption(kind) => view.set_message(log_interruption(link, kind, &input).await?),
+        PromptKind::Abandon { strict_phrase: None } if !input.eq_ignore_ascii_case("yes") => {
+            view.set_message("Work session not abandoned");
+        }
+        PromptKind::Abandon { strict_phrase } => {
+            let confirmation = strict_phrase.map(|_| input);
+            view.set_message(abandon_session(link, confirmation).await?);
+        }
+    }
     Ok(())
 }
+
+/// Ask for the reason for an interruption, if there's a work session to log it against
+fn open_interruption_prompt(kind: InterruptionKind, status: &PomodoroStatus, view: &mut View) {
+    if status.state == PomodoroState::Work || status.prev_state == Some(PomodoroState::Work) {
+        view.open_prompt(PromptKind::Interruption(kind), String::new());
+    } else {
+        view.set_message("Interruptions can only be logged during a work session");
+    }
+}
+
+/// Confirm abandoning the work session, if there is one
+fn open_abandon_prompt(status: &PomodoroStatus, view: &mut View) {
+    if status.state == PomodoroState::Work || status.prev_state == Some(PomodoroState::Work) {
+        view.open_prompt(PromptKind::Abandon { strict_phrase: status.strict_phrase.clone() }, String::new());
+    } else {
+        view.set_message("No work session to abandon");
+    }
+}
+
+/// Abandon the current work session and describe the outcome
+async fn abandon_session(link: &mut TimerLink<'_>, confirmation: Option<String>) -> Result<String, ControlError> {
+    match link.request(PomodoroCommand::Abandon { confirmation }).await {
+        Ok(()) => Ok("Work session abandoned".to_string()),
+        Err(ControlError::Remote { message, .. }) => Ok(message),
+        Err(e) => Err(e),
+    }
+}
+
+/// Send a start, pause, toggle or skip. A rejection (e.g. starting a running timer, or
+/// pausing in strict mode) is returned so the user knows why nothing happened.
+async fn send_checked(link: &mut TimerLink<'_>, command: PomodoroCommand) -> Result<Option<String>, ControlError> {
+    match link.request(command).await {
+        Ok(()) => Ok(None),
+        Err(ControlError::Remote { message, .. }) => Ok(Some(message)),
+        Err(e) => Err(e),
+    }
+}
+
+/// The interval after an extension, and how many more extensions the cycle allows
+fn describe_extension(status: &PomodoroStatus) -> String {
+    let mut text = format!("Extended the {} to {} left", session_name(status), plural(minutes_left(status), "minute"));
+    if let Some(left) = status.extensions_left {
+        text.push_str(&format!(", {} left this cycle", plural(left as i64, "extension")));
+    }
+    text
+}
+
+/// Extend the current interval and describe the outcome
+async fn extend_interval(link: &mut TimerLink<'_>, minutes: Option<u32>) -> Result<String, ControlError> {
+    match link.request(PomodoroCommand::Extend { minutes }).await {
+        Ok(()) => Ok(describe_extension(&link.status().await?)),
+        Err(ControlError::Remote { message, .. }) => Ok(message),
+        Err(e) => Err(e),
+    }
+}
+
+/// Save a note through the timer, so it's linked to the running work session, and describe the outcome
+async fn save_note(link: &mut TimerLink<'_>, text: &str) -> Result<String, ControlError> {
+    match link.request(PomodoroCommand::AddNote { text: text.to_string() }).await {
+        Ok(()) => Ok("Note saved".to_string()),
+        Err(ControlError::Remote { message, .. }) => Ok(format!("Failed to save note: {}", message)),
+        Err(e) => Err(e),
+    }
+}
+
+/// Log an interruption of the current work session and describe the outcome
+async fn log_interruption(
+    link: &mut TimerLink<'_>,
+    kind: InterruptionKind,
+    reason: &str,
+) -> Result<String, ControlError> {
+    let reason = (!reason.is_empty()).then(|| reason.to_string());
+    match link.request(PomodoroCommand::Interrupt { kind, reason }).await {
+        Ok(()) => Ok(format!("{} interruption logged", kind.label())),
+        Err(ControlError::Remote { message, .. }) => Ok(message),
+        Err(e) => Err(e),
+    }
+}
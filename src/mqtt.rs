@@ -0,0 +1,237 @@
+use std::time::{Duration as StdDuration, Instant};
+
+use rumqttc::{AsyncClient, Event, LastWill, MqttOptions, Packet, QoS};
+use thiserror::Error;
+use tokio::sync::{mpsc, watch};
+
+use crate::pomodoro::{PomodoroCommand, PomodoroStatus};
+
+// Delay before reconnecting after the broker drops or refuses the connection
+const RECONNECT_DELAY_SECS: u64 = 5;
+// Minimum gap between `remaining` updates while an interval counts down, to keep broker traffic low
+const REMAINING_PUBLISH_INTERVAL: StdDuration = StdDuration::from_secs(5);
+
+#[derive(Error, Debug)]
+pub enum MqttError {
+    #[error("MQTT client error: {0}")]
+    Client(#[from] rumqttc::ClientError),
+}
+
+#[derive(Debug, Clone)]
+pub struct MqttConfig {
+    pub host: String,
+    pub port: u16,
+    pub client_id: String,
+    pub topic_prefix: String,
+}
+
+impl MqttConfig {
+    fn topic(&self, name: &str) -> String {
+        format!("{}/{}", self.topic_prefix.trim_end_matches('/'), name)
+    }
+}
+
+/// Map a payload received on the command topic to a timer command
+fn parse_command(payload: &[u8]) -> Option<PomodoroCommand> {
+    let text = std::str::from_utf8(payload).ok()?;
+    match text.trim().to_ascii_lowercase().as_str() {
+        "start" | "resume" => Some(PomodoroCommand::start()),
+        "stop" | "pause" => Some(PomodoroCommand::Stop),
+        "toggle" => Some(PomodoroCommand::Toggle),
+        "next" | "skip" => Some(PomodoroCommand::Next),
+        "extend" | "snooze" => Some(PomodoroCommand::Extend { minutes: None }),
+        _ => None,
+    }
+}
+
+/// Retained topic/payload pairs describing a status snapshot
+fn status_messages(config: &MqttConfig, status: &PomodoroStatus) -> Vec<(String, String)> {
+    vec![
+        (config.topic("state"), status.state.as_str().to_string()),
+        remaining_message(config, status),
+        (config.topic("task"), status.task.clone().unwrap_or_default()),
+    ]
+}
+
+/// Retained topic/payload pair for the time left in the current interval
+fn remaining_message(config: &MqttConfig, status: &PomodoroStatus) -> (String, String) {
+    (config.topic("remaining"), status.remaining_seconds.to_string())
+}
+
+fn publish(client: &AsyncClient, messages: Vec<(String, String)>) -> Result<(), MqttError> {
+    for (topic, payload) in messages {
+        client.try_publish(topic, QoS::AtLeastOnce, true, payload)?;
+    }
+    Ok(())
+}
+
+/// Bridge the timer to an MQTT broker until the status channel closes.
+///
+/// Retained `state`, `remaining` and `task` topics are published on every transition, `remaining`
+/// is also refreshed while the clock runs (at most every few seconds), and payloads on the `command` topic (`start`, `stop`, `next`, `extend`) are forwarded to the timer.
+/// Connection and publish errors go to `log_tx` rather than stderr, which may be under the interactive view.
+pub async fn run_mqtt_bridge(
+    config: MqttConfig,
+    mut status_rx: watch::Receiver<PomodoroStatus>,
+    command_tx: mpsc::Sender<PomodoroCommand>,
+    log_tx: mpsc::UnboundedSender<String>,
+) -> Result<(), MqttError> {
+    let mut options = MqttOptions::new(config.client_id.clone(), config.host.clone(), config.port);
+    options.set_keep_alive(StdDuration::from_secs(30));
+    // Let subscribers know the timer went away if we disconnect uncleanly
+    options.set_last_will(LastWill::new(config.topic("state"), "offline", QoS::AtLeastOnce, true));
+
+    let (client, mut eventloop) = AsyncClient::new(options, 32);
+    let command_topic = config.topic("command");
+    let mut last_published = status_rx.borrow().clone();
+    let mut remaining_published_at = Instant::now();
+    // Nothing is published while disconnected, since every connection republishes the full state
+    let mut connected = false;
+    // Only the first failed attempt of an outage is reported, not every retry
+    let mut outage_reported = false;
+
+    loop {
+        tokio::select! {
+            event = eventloop.poll() => {
+                match event {
+                    Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                        // (Re)subscribe and republish on every connection since the session is not persistent
+                        if let Err(e) = client.try_subscribe(command_topic.clone(), QoS::AtLeastOnce) {
+                            let _ = log_tx.send(format!("Failed to subscribe to {}: {}", command_topic, e));
+                        }
+                        if outage_reported {
+                            let _ = log_tx.send(format!("MQTT connected to {}:{}", config.host, config.port));
+                            outage_reported = false;
+                        }
+                        connected = true;
+                        last_published = status_rx.borrow().clone();
+                        remaining_published_at = Instant::now();
+                        if let Err(e) = publish(&client, status_messages(&config, &last_published)) {
+                            let _ = log_tx.send(format!("Failed to publish MQTT status: {}", e));
+                        }
+                    }
+                    Ok(Event::Incoming(Packet::Publish(publish))) => {
+                        if publish.topic == command_topic {
+                            match parse_command(&publish.payload) {
+                                Some(command) => {
+                                    if command_tx.send(command).await.is_err() {
+                                        // Timer has shut down
+                                        break;
+                                    }
+                                }
+                                None => {
+                                    let payload = String::from_utf8_lossy(&publish.payload);
+                                    let _ = log_tx.send(format!("Ignoring unknown MQTT command: {}", payload));
+                                }
+                            }
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        connected = false;
+                        if !outage_reported {
+                            let _ = log_tx.send(format!("MQTT connection error: {} - retrying every {}s", e, RECONNECT_DELAY_SECS));
+                            outage_reported = true;
+                        }
+                        tokio::time::sleep(StdDuration::from_secs(RECONNECT_DELAY_SECS)).await;
+                    }
+                }
+            }
+
+            changed = status_rx.changed() => {
+                if changed.is_err() {
+                    // Timer task finished
+                    break;
+                }
+
+                let status = status_rx.borrow_and_update().clone();
+                if !connected {
+                    continue;
+                }
+                if status.is_transition_from(&last_published) {
+                    if let Err(e) = publish(&client, status_messages(&config, &status)) {
+                        let _ = log_tx.send(format!("Failed to publish MQTT status: {}", e));
+                    }
+                    remaining_published_at = Instant::now();
+                    last_published = status;
+                } else if status.remaining_seconds != last_published.remaining_seconds
+                    && remaining_published_at.elapsed() >= REMAINING_PUBLISH_INTERVAL
+                {
+                    if let Err(e) = publish(&client, vec![remaining_message(&config, &status)]) {
+                        let _ = log_tx.send(format!("Failed to publish MQTT status: {}", e));
+                    }
+                    remaining_published_at = Instant::now();
+                    last_published = status;
+                }
+            }
+        }
+    }
+
+    // Best-effort offline marker; the broker may already be unreachable
+    let _ = client.try_publish(config.topic("state"), QoS::AtLeastOnce, true, "offline");
+    let _ = client.try_disconnect();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pomodoro::PomodoroState;
+
+    fn config(topic_prefix: &str) -> MqttConfig {
+        MqttConfig {
+            host: "localhost".into(),
+            port: 1883,
+            client_id: "test".into(),
+            topic_prefix: topic_prefix.into(),
+        }
+    }
+
+    #[test]
+    fn parses_commands_and_aliases() {
+        assert_eq!(parse_command(b"start"), Some(PomodoroCommand::start()));
+        assert_eq!(parse_command(b"resume"), Some(PomodoroCommand::start()));
+        assert_eq!(parse_command(b"pause"), Some(PomodoroCommand::Stop));
+        assert_eq!(parse_command(b" Toggle\n"), Some(PomodoroCommand::Toggle));
+        assert_eq!(parse_command(b"SKIP"), Some(PomodoroCommand::Next));
+        assert_eq!(parse_command(b"snooze"), Some(PomodoroCommand::Extend { minutes: None }));
+    }
+
+    #[test]
+    fn rejects_unknown_commands() {
+        assert_eq!(parse_command(b"explode"), None);
+        assert_eq!(parse_command(b""), None);
+        assert_eq!(parse_command(&[0xff, 0xfe]), None);
+    }
+
+    #[test]
+    fn topics_ignore_trailing_slash() {
+        assert_eq!(config("pomodoro").topic("state"), "pomodoro/state");
+        assert_eq!(config("home/desk/").topic("command"), "home/desk/command");
+    }
+
+    #[test]
+    fn status_messages_cover_state_remaining_and_task() {
+        let status = PomodoroStatus {
+            state: PomodoroState::Work,
+            remaining_seconds: -42,
+            task: Some("Write report".into()),
+            ..PomodoroStatus::default()
+        };
+        assert_eq!(
+            status_messages(&config("pomodoro"), &status),
+            vec![
+                ("pomodoro/state".to_string(), "work".to_string()),
+                ("pomodoro/remaining".to_string(), "-42".to_string()),
+                ("pomodoro/task".to_string(), "Write report".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_task_publishes_empty_payload() {
+        let messages = status_messages(&config("pomodoro"), &PomodoroStatus::default());
+        assert_eq!(messages[2], ("pomodoro/task".to_string(), String::new()));
+    }
+}
@@ -1,7 +1,8 @@
 use notify_rust::Notification;
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Write};
 use std::sync::{Arc, Mutex};
 use std::ops::Deref;
+use tokio::sync::mpsc;
 
 use crate::sound::SoundPlayer;
 
@@ -76,7 +77,10 @@ impl Notifier for SoundNotifier {
 }
 
 // Desktop notification implementation
-pub struct DesktopNotifier;
+pub struct DesktopNotifier {
+    // Failures are reported here, since stderr may be under the interactive view
+    log_tx: mpsc::UnboundedSender<String>,
+}
 
 impl Notifier for DesktopNotifier {
     fn notify(&self, title: &str, message: &str) {
@@ -86,19 +90,25 @@ impl Notifier for DesktopNotifier {
             .timeout(5000) // 5 seconds
             .show() 
         {
-            eprintln!("Failed to show desktop notification: {} - Check if your system supports notifications", e);
+            let _ = self.log_tx.send(format!(
+                "Failed to show desktop notification: {} - Check if your system supports notifications",
+                e
+            ));
         }
     }
 }
 
 // Terminal notification implementation for systems without desktop notification support
-pub struct TerminalNotifier;
+pub struct TerminalNotifier {
+    // Plain ASCII instead of the bell emoji
+    pub ascii: bool,
+}
 
 impl Notifier for TerminalNotifier {
     fn notify(&self, title: &str, message: &str) {
         println!("\n\x07"); // Bell character
         println!("======================================");
-        println!("🔔 {}", title);
+        println!("{} {}", if self.ascii { "*" } else { "🔔" }, title);
         println!("   {}", message);
         println!("======================================");
         // Use a more robust approach to handle potential flush errors
@@ -108,22 +118,125 @@ impl Notifier for TerminalNotifier {
     }
 }
 
+/// Escape sequence flavours for desktop notifications sent through the terminal
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OscProtocol {
+    /// `OSC 9`: iTerm2, WezTerm, kitty, Windows Terminal and others
+    Osc9,
+    /// `OSC 777`: foot, rxvt-unicode (with the notify extension) and VTE-based terminals
+    Osc777,
+}
+
+impl OscProtocol {
+    /// Guess which protocol the terminal understands from its environment
+    fn detect() -> Self {
+        let term = std::env::var("TERM").unwrap_or_default();
+        let vte = std::env::var_os("VTE_VERSION").is_some();
+        if term.starts_with("foot") || term.starts_with("rxvt") || vte {
+            OscProtocol::Osc777
+        } else {
+            OscProtocol::Osc9
+        }
+    }
+}
+
+// Notifications sent as terminal escape sequences, which the terminal turns into desktop
+// notifications. They reach the user's desktop even over SSH, where DesktopNotifier can't.
+pub struct TerminalOscNotifier {
+    protocol: OscProtocol,
+    // Inside tmux the sequence has to be passed through to the outer terminal
+    tmux: bool,
+    // Failures are reported here rather than on the terminal the sequence was meant for
+    log_tx: mpsc::UnboundedSender<String>,
+}
+
+impl TerminalOscNotifier {
+    pub fn new(protocol: OscProtocol, log_tx: mpsc::UnboundedSender<String>) -> Self {
+        Self { protocol, tmux: std::env::var_os("TMUX").is_some(), log_tx }
+    }
+}
+
+impl Notifier for TerminalOscNotifier {
+    fn notify(&self, title: &str, message: &str) {
+        // Control characters would end the sequence early, and OSC 777 separates fields with ';'
+        let clean = |text: &str| -> String {
+            text.chars()
+                .filter(|ch| !ch.is_control())
+                .map(|ch| if ch == ';' && self.protocol == OscProtocol::Osc777 { ',' } else { ch })
+                .collect()
+        };
+        let mut sequence = match self.protocol {
+            OscProtocol::Osc9 => format!("\x1b]9;{}: {}\x07", clean(title), clean(message)),
+            OscProtocol::Osc777 => format!("\x1b]777;notify;{};{}\x07", clean(title), clean(message)),
+        };
+        if self.tmux {
+            // tmux passthrough (needs `allow-passthrough on`): wrap, doubling every escape
+            sequence = format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"));
+        }
+        
+        let mut stdout = io::stdout();
+        if let Err(e) = stdout.write_all(sequence.as_bytes()).and_then(|_| stdout.flush()) {
+            let _ = self.log_tx.send(format!("Failed to send terminal notification: {}", e));
+        }
+    }
+}
+
+/// Which notifier to use, chosen with `--notifier`
+#[derive(Debug, Clone, Copy, PartialEq, Default, clap::ValueEnum)]
+pub enum NotifierKind {
+    /// Terminal escape sequences over SSH, otherwise desktop notifications with a terminal fallback
+    #[default]
+    Auto,
+    /// Desktop notifications, falling back to the terminal when unavailable
+    Desktop,
+    /// A banner printed in the terminal
+    Terminal,
+    /// OSC 9 escape sequences
+    Osc9,
+    /// OSC 777 escape sequences
+    Osc777,
+}
+
+// Pick the notifier for `kind`. Problems showing notifications are reported on `log_tx`.
+pub fn get_notifier(kind: NotifierKind, ascii: bool, log_tx: mpsc::UnboundedSender<String>) -> Arc<dyn Notifier + Send + Sync> {
+    match kind {
+        NotifierKind::Auto => {
+            // Over SSH desktop notifications would show up on the remote machine, if anywhere
+            let over_ssh = std::env::var_os("SSH_TTY").is_some() || std::env::var_os("SSH_CONNECTION").is_some();
+            if over_ssh && io::stdout().is_terminal() {
+                Arc::new(TerminalOscNotifier::new(OscProtocol::detect(), log_tx))
+            } else {
+                get_default_notifier(ascii, log_tx)
+            }
+        }
+        NotifierKind::Desktop => get_default_notifier(ascii, log_tx),
+        NotifierKind::Terminal => Arc::new(TerminalNotifier { ascii }),
+        NotifierKind::Osc9 => Arc::new(TerminalOscNotifier::new(OscProtocol::Osc9, log_tx)),
+        NotifierKind::Osc777 => Arc::new(TerminalOscNotifier::new(OscProtocol::Osc777, log_tx)),
+    }
+}
+
 // Detect the best notification system to use
-pub fn get_default_notifier() -> Arc<dyn Notifier + Send + Sync> {
+pub fn get_default_notifier(ascii: bool, log_tx: mpsc::UnboundedSender<String>) -> Arc<dyn Notifier + Send + Sync> {
     // Try to create a desktop notification, with a timeout to avoid hanging
     match Notification::new().summary("Pomodoro").body("Initializing...").timeout(1000).show() {
-        Ok(_) => Arc::new(DesktopNotifier),
+        Ok(_) => Arc::new(DesktopNotifier { log_tx }),
         Err(e) => {
-            eprintln!("Desktop notifications not available ({}), falling back to terminal", e);
-            Arc::new(TerminalNotifier)
+            let _ = log_tx.send(format!("Desktop notifications not available ({}), falling back to terminal", e));
+            Arc::new(TerminalNotifier { ascii })
         }
     }
 }
 
 // Get a notifier with sound support
-pub fn get_sound_notifier(sound_enabled: bool) -> Arc<dyn Notifier + Send + Sync> {
+pub fn get_sound_notifier(
+    sound_enabled: bool,
+    kind: NotifierKind,
+    ascii: bool,
+    log_tx: mpsc::UnboundedSender<String>,
+) -> Arc<dyn Notifier + Send + Sync> {
     // Get a base notifier first
-    let base_notifier = get_default_notifier();
+    let base_notifier = get_notifier(kind, ascii, log_tx);
     
     // Get a sound player
     let sound_player = crate::sound::get_default_sound_player(sound_enabled);
@@ -1,13 +1,15 @@
 use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
 use std::sync::{Arc, Mutex};
 use thiserror::Error;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 use tokio::time;
 
-use crate::db::{Database, DatabaseError};
+use crate::db::{Database, DatabaseError, SessionOutcome, TimerSnapshot};
 use crate::notification::{Notifier, NotificationSound};
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum PomodoroState {
     Idle,
     Work,
@@ -16,12 +18,215 @@ pub enum PomodoroState {
     Paused,
 }
 
-#[derive(Debug, Clone, Copy)]
+impl PomodoroState {
+    /// Stable lowercase name, used for session types and external integrations
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PomodoroState::Idle => "idle",
+            PomodoroState::Work => "work",
+            PomodoroState::ShortBreak => "short_break",
+            PomodoroState::LongBreak => "long_break",
+            PomodoroState::Paused => "paused",
+        }
+    }
+    
+    /// Human-readable name for display
+    pub fn label(&self) -> &'static str {
+        match self {
+            PomodoroState::Idle => "Idle",
+            PomodoroState::Work => "Working",
+            PomodoroState::ShortBreak => "Short Break",
+            PomodoroState::LongBreak => "Long Break",
+            PomodoroState::Paused => "Paused",
+        }
+    }
+    
+    /// Inverse of `as_str`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "idle" => Some(PomodoroState::Idle),
+            "work" => Some(PomodoroState::Work),
+            "short_break" => Some(PomodoroState::ShortBreak),
+            "long_break" => Some(PomodoroState::LongBreak),
+            "paused" => Some(PomodoroState::Paused),
+            _ => None,
+        }
+    }
+}
+
+/// Cirillo's two kinds of interruption: an urge of your own, or someone else asking for attention
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum InterruptionKind {
+    Internal,
+    External,
+}
+
+impl InterruptionKind {
+    /// Stable lowercase name, stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InterruptionKind::Internal => "internal",
+            InterruptionKind::External => "external",
+        }
+    }
+    
+    /// Human-readable name for display
+    pub fn label(&self) -> &'static str {
+        match self {
+            InterruptionKind::Internal => "Internal",
+            InterruptionKind::External => "External",
+        }
+    }
+}
+
+/// Which intervals start by themselves when the one before runs out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum AutoStart {
+    /// Every interval waits for you to start it
+    #[default]
+    Off,
+    /// Breaks start when a work session runs out
+    Breaks,
+    /// Work sessions start when a break runs out
+    Work,
+    /// Breaks and work sessions both start by themselves
+    All,
+    /// Like all, but the work session after the long break waits for you
+    Cycle,
+}
+
+impl AutoStart {
+    /// Whether `next` starts by itself once `finished` runs out
+    fn applies(&self, finished: PomodoroState, next: PomodoroState) -> bool {
+        let next_is_break = matches!(next, PomodoroState::ShortBreak | PomodoroState::LongBreak);
+        match self {
+            AutoStart::Off => false,
+            AutoStart::Breaks => next_is_break,
+            AutoStart::Work => next == PomodoroState::Work,
+            AutoStart::All => true,
+            AutoStart::Cycle => finished != PomodoroState::LongBreak,
+        }
+    }
+}
+
+/// One step of a custom interval sequence
+#[derive(Debug, Clone, PartialEq)]
+pub struct Interval {
+    /// Work, short break or long break, which decides how the step behaves
+    pub state: PomodoroState,
+    /// Recorded as the session type of a work step, and shown while the step is on
+    pub name: String,
+    pub duration: Duration,
+}
+
+impl Interval {
+    /// Parse `KIND:MINUTES[:NAME]`, where KIND is `work`, `break` (a short break),
+    /// `short_break` or `long_break`. Without a name, the step takes the name of its kind.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut parts = text.trim().split(':').map(str::trim);
+        let kind = parts.next().unwrap_or_default();
+        let state = match kind {
+            "work" => PomodoroState::Work,
+            "break" | "short_break" => PomodoroState::ShortBreak,
+            "long_break" => PomodoroState::LongBreak,
+            _ => return Err(format!("`{}` isn't an interval kind; use work, break, short_break or long_break", kind)),
+        };
+        let minutes: i64 = match parts.next() {
+            Some(minutes) => minutes.parse().map_err(|_| format!("`{}` isn't a number of minutes", minutes))?,
+            None => return Err(format!("`{}` needs a length, like {}:25", text.trim(), kind)),
+        };
+        if minutes < 1 {
+            return Err(format!("`{}` must last at least a minute", text.trim()));
+        }
+        let name = parts.next().unwrap_or(state.as_str()).to_lowercase();
+        if parts.next().is_some() {
+            return Err(format!("`{}` has too many parts; use KIND:MINUTES[:NAME]", text.trim()));
+        }
+        
+        // Built-in names keep their meaning, so stats can tell work from breaks
+        let reserved = PomodoroState::from_name(&name).is_some_and(|named| named != state);
+        if name.is_empty() || reserved || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!("`{}` can't name a {} interval; use letters, digits and underscores", name, kind));
+        }
+        
+        Ok(Self { state, name, duration: Duration::minutes(minutes) })
+    }
+    
+    /// Human-readable name for display: the usual label of its kind, unless the
+    /// step has a name of its own, which is shown in title case
+    pub fn label(&self) -> String {
+        if self.name == self.state.as_str() {
+            return self.state.label().to_string();
+        }
+        self.name
+            .split('_')
+            .filter(|word| !word.is_empty())
+            .map(|word| {
+                let mut chars = word.chars();
+                chars.next().map_or(String::new(), |first| first.to_uppercase().chain(chars).collect())
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl std::fmt::Display for Interval {
+    /// The `KIND:MINUTES[:NAME]` form accepted by `parse`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.state.as_str(), self.duration.num_minutes())?;
+        if self.name != self.state.as_str() {
+            write!(f, ":{}", self.name)?;
+        }
+        Ok(())
+    }
+}
+
+/// How work sessions are timed
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimerMode {
+    /// Pomodoros of a fixed length, followed by fixed breaks
+    Fixed,
+    /// Work sessions count up until the user ends them, and earn a break of
+    /// `break_fraction` of their length
+    Flowtime { break_fraction: f64 },
+    /// A custom sequence of intervals, repeated in order. It starts with a work interval.
+    Sequence(Vec<Interval>),
+}
+
+#[derive(Debug, Clone)]
 pub struct PomodoroConfig {
     pub work_duration: Duration,
     pub short_break_duration: Duration,
     pub long_break_duration: Duration,
     pub long_break_after: usize,
+    pub mode: TimerMode,
+    pub auto_start: AutoStart,
+    /// Countdown before an auto-started interval begins, during which it can be cancelled
+    pub auto_start_grace: Duration,
+    /// Keep a work session running past zero until the user moves on
+    pub overtime: bool,
+    /// How often to remind the user while in overtime; zero for no reminders
+    pub overtime_reminder: Duration,
+    /// How much an extension adds when no length is given
+    pub extend_by: Duration,
+    /// Most extensions allowed per long-break cycle; `None` for no limit
+    pub max_extensions: Option<usize>,
+    /// In strict mode, the phrase that must be typed to abandon a work session.
+    /// Work sessions can't be paused or skipped. `None` when strict mode is off.
+    pub strict_phrase: Option<String>,
+}
+
+impl PomodoroConfig {
+    /// Full length of an interval in seconds; zero for states that aren't intervals
+    pub fn interval_seconds(&self, state: PomodoroState) -> i64 {
+        match state {
+            PomodoroState::Work => self.work_duration.num_seconds(),
+            PomodoroState::ShortBreak => self.short_break_duration.num_seconds(),
+            PomodoroState::LongBreak => self.long_break_duration.num_seconds(),
+            _ => 0,
+        }
+    }
 }
 
 impl Default for PomodoroConfig {
@@ -31,10 +236,26 @@ impl Default for PomodoroConfig {
             short_break_duration: Duration::minutes(5),
             long_break_duration: Duration::minutes(15),
             long_break_after: 4,
+            mode: TimerMode::Fixed,
+            auto_start: AutoStart::Off,
+            auto_start_grace: Duration::zero(),
+            overtime: false,
+            overtime_reminder: Duration::minutes(5),
+            extend_by: Duration::minutes(5),
+            max_extensions: None,
+            strict_phrase: None,
         }
     }
 }
 
+// Shortest break a flowtime work session earns, however brief it was
+const MIN_FLOWTIME_BREAK_SECONDS: i64 = 60;
+
+/// Break earned by working `worked_seconds` in flowtime
+fn flowtime_break_seconds(worked_seconds: i64, break_fraction: f64) -> i64 {
+    ((worked_seconds.max(0) as f64 * break_fraction).round() as i64).max(MIN_FLOWTIME_BREAK_SECONDS)
+}
+
 #[derive(Error, Debug)]
 pub enum PomodoroError {
     #[error("Timer already running")]
@@ -43,17 +264,203 @@ pub enum PomodoroError {
     #[error("Timer not running")]
     NotRunning,
     
+    #[error("No work session in progress")]
+    NotWorking,
+    
+    #[error("No more extensions this cycle (limit {0})")]
+    ExtensionLimit(usize),
+    
+    #[error("Extensions must add at least a minute")]
+    EmptyExtension,
+    
+    #[error("Flowtime work sessions have no end to extend")]
+    OpenEnded,
+    
+    #[error("Strict mode: work sessions can't be paused or skipped, only abandoned")]
+    Strict,
+    
+    #[error("Strict mode: type \"{0}\" to abandon this work session")]
+    ConfirmationRequired(String),
+    
     #[error("Database error: {0}")]
     Database(#[from] DatabaseError),
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub enum PomodoroCommand {
-    Start,
+    /// Start or resume. If a project or task is given, it replaces the active one first.
+    Start {
+        project: Option<String>,
+        task: Option<String>,
+    },
     Stop,
+    Toggle,
     Next,
+    /// Replace the active project and task; `None` clears them
+    SetTask {
+        project: Option<String>,
+        task: Option<String>,
+    },
+    /// Log an interruption of the current work session
+    Interrupt {
+        kind: InterruptionKind,
+        reason: Option<String>,
+    },
+    /// Add time to the current interval, running or paused; `None` uses the configured amount
+    Extend {
+        minutes: Option<u32>,
+    },
+    /// Give up on the current work session. Strict mode requires its confirmation phrase.
+    Abandon {
+        confirmation: Option<String>,
+    },
+    /// Jot down a note, linked to the current work session if there is one
+    AddNote {
+        text: String,
+    },
     Shutdown,
 }
 
+impl PomodoroCommand {
+    /// Plain start/resume without changing the active task
+    pub fn start() -> Self {
+        PomodoroCommand::Start { project: None, task: None }
+    }
+}
+
+/// Point-in-time view of the timer, published to observers after every tick or command
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PomodoroStatus {
+    pub state: PomodoroState,
+    pub prev_state: Option<PomodoroState>,
+    pub remaining_seconds: i64,
+    /// Full length of the current interval (or the one that resumes when paused)
+    pub duration_seconds: i64,
+    pub completed_pomodoros: usize,
+    /// Work intervals per long-break cycle
+    pub long_break_after: usize,
+    pub project: Option<String>,
+    pub task: Option<String>,
+    /// Seconds until the paused interval starts by itself, when an auto-start is pending
+    #[serde(default)]
+    pub auto_start_in: Option<i64>,
+    /// Extensions still allowed this cycle; `None` when there is no limit
+    #[serde(default)]
+    pub extensions_left: Option<usize>,
+    /// Whether work sessions keep counting past zero instead of ending
+    #[serde(default)]
+    pub overtime: bool,
+    /// Phrase that abandons a work session in strict mode; `None` when strict mode is off
+    #[serde(default)]
+    pub strict_phrase: Option<String>,
+    /// In flowtime mode, the share of a work session's length earned as a break; `None` otherwise
+    #[serde(default)]
+    pub break_fraction: Option<f64>,
+    /// With a custom sequence, the current or pending step; `None` otherwise
+    #[serde(default)]
+    pub sequence: Option<SequenceStep>,
+    /// Database id of the open work session, running or paused; `None` when there is none
+    #[serde(default)]
+    pub session_id: Option<i64>,
+}
+
+/// Where the timer is in a custom interval sequence
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SequenceStep {
+    /// Name of the interval, recorded as the session type of a work interval
+    pub name: String,
+    /// Name of the interval for display
+    pub label: String,
+    /// Position in the sequence, counting from 1
+    pub step: usize,
+    /// Number of intervals in the sequence
+    pub steps: usize,
+}
+
+impl Default for PomodoroStatus {
+    fn default() -> Self {
+        Self {
+            state: PomodoroState::Idle,
+            prev_state: None,
+            remaining_seconds: 0,
+            duration_seconds: 0,
+            completed_pomodoros: 0,
+            long_break_after: PomodoroConfig::default().long_break_after,
+            project: None,
+            task: None,
+            auto_start_in: None,
+            extensions_left: None,
+            overtime: false,
+            strict_phrase: None,
+            break_fraction: None,
+            sequence: None,
+            session_id: None,
+        }
+    }
+}
+
+impl PomodoroStatus {
+    /// Whether this differs from `previous` by more than the ticking countdown
+    pub fn is_transition_from(&self, previous: &PomodoroStatus) -> bool {
+        self.state != previous.state
+            || self.prev_state != previous.prev_state
+            || self.completed_pomodoros != previous.completed_pomodoros
+            || self.project != previous.project
+            || self.task != previous.task
+            || self.auto_start_in.is_some() != previous.auto_start_in.is_some()
+            || self.is_overtime() != previous.is_overtime()
+            || self.sequence != previous.sequence
+    }
+    
+    /// Display name of the current interval, or the one that resumes when paused. In a
+    /// custom sequence this is the interval's own name.
+    pub fn interval_label(&self) -> String {
+        match (self.state, self.prev_state, &self.sequence) {
+            (PomodoroState::Idle, _, _) => PomodoroState::Idle.label().to_string(),
+            (_, _, Some(sequence)) => sequence.label.clone(),
+            (PomodoroState::Paused, Some(next), None) => next.label().to_string(),
+            (state, _, None) => state.label().to_string(),
+        }
+    }
+    
+    /// On a work session, whether it's running or paused (including one that's ready to start)
+    fn is_work(&self) -> bool {
+        let state = match self.state {
+            PomodoroState::Paused => self.prev_state,
+            state => Some(state),
+        };
+        state == Some(PomodoroState::Work)
+    }
+    
+    /// Whether a work session, running or paused, has run past its planned length in
+    /// overtime mode. `remaining_seconds` then counts the overtime as a negative number.
+    /// Without overtime mode a session paused right at zero has simply run out.
+    pub fn is_overtime(&self) -> bool {
+        self.overtime && self.is_work() && self.duration_seconds > 0 && self.remaining_seconds <= 0
+    }
+    
+    /// Whether this is a flowtime work session, which counts up with no fixed end.
+    /// `remaining_seconds` then holds the time worked so far as a negative number.
+    pub fn is_open_ended(&self) -> bool {
+        self.is_work() && self.break_fraction.is_some()
+    }
+    
+    /// Length of the break a flowtime work session has earned so far
+    pub fn earned_break_seconds(&self) -> Option<i64> {
+        let break_fraction = self.break_fraction.filter(|_| self.is_open_ended())?;
+        Some(flowtime_break_seconds(-self.remaining_seconds, break_fraction))
+    }
+    
+    /// On a break, whether it's running or paused (including one that's ready to start)
+    pub fn is_break(&self) -> bool {
+        let state = match self.state {
+            PomodoroState::Paused => self.prev_state,
+            state => Some(state),
+        };
+        matches!(state, Some(PomodoroState::ShortBreak | PomodoroState::LongBreak))
+    }
+}
+
 pub struct Pomodoro {
     state: PomodoroState,
     prev_state: Option<PomodoroState>,  // To remember state before pausing
@@ -62,8 +469,24 @@ pub struct Pomodoro {
     current_session_id: Option<i64>,
     start_time: Option<DateTime<Local>>,
     remaining_seconds: i64,
+    // When the paused interval starts by itself, if an auto-start is pending
+    auto_start_at: Option<DateTime<Local>>,
+    // Overtime notifications sent for the current work session
+    overtime_notices: i64,
+    // Time added to the current interval by extensions
+    extension_seconds: i64,
+    // Extensions since the last long break, for the per-cycle limit
+    cycle_extensions: usize,
+    // Length of the break lined up after a flowtime work session
+    earned_break_seconds: i64,
+    // Position of the current or pending interval in a custom sequence
+    sequence_index: usize,
+    project: Option<String>,
+    task: Option<String>,
     database: Arc<Database>,
     notifier: Arc<dyn Notifier + Send + Sync>,
+    // Where problems nobody asked about are reported, since stderr may be under the interactive view
+    log_tx: mpsc::UnboundedSender<String>,
 }
 
 impl Pomodoro {
@@ -71,6 +494,7 @@ impl Pomodoro {
         config: PomodoroConfig, 
         database: Arc<Database>,
         notifier: Arc<dyn Notifier + Send + Sync>,
+        log_tx: mpsc::UnboundedSender<String>,
     ) -> Self {
         Self {
             state: PomodoroState::Idle,
@@ -80,24 +504,116 @@ impl Pomodoro {
             current_session_id: None,
             start_time: None,
             remaining_seconds: 0,
+            auto_start_at: None,
+            overtime_notices: 0,
+            extension_seconds: 0,
+            cycle_extensions: 0,
+            earned_break_seconds: 0,
+            sequence_index: 0,
+            project: None,
+            task: None,
             database,
             notifier,
+            log_tx,
         }
     }
     
+    /// Report a problem with something the timer did on its own, or with a command whose sender doesn't wait for the outcome
+    fn log(&self, message: String) {
+        let _ = self.log_tx.send(message);
+    }
+    
     pub fn get_state(&self) -> PomodoroState {
         self.state
     }
     
-    pub fn get_remaining_seconds(&self) -> i64 {
-        self.remaining_seconds
+    /// Length of an interval of `state`, including any extensions of the current one.
+    /// Zero for flowtime work sessions, which have no fixed length. In a custom sequence,
+    /// the length of the current or pending step.
+    fn interval_seconds(&self, state: PomodoroState) -> i64 {
+        let seconds = match (&self.config.mode, state) {
+            (TimerMode::Flowtime { .. }, PomodoroState::Work) => 0,
+            (TimerMode::Flowtime { .. }, PomodoroState::ShortBreak | PomodoroState::LongBreak) => self.earned_break_seconds,
+            (TimerMode::Sequence(_), PomodoroState::Work | PomodoroState::ShortBreak | PomodoroState::LongBreak) => {
+                self.current_interval().map_or(0, |interval| interval.duration.num_seconds())
+            }
+            _ => self.config.interval_seconds(state),
+        };
+        match seconds {
+            0 => 0,
+            seconds => seconds + self.extension_seconds,
+        }
+    }
+    
+    fn is_flowtime(&self) -> bool {
+        matches!(self.config.mode, TimerMode::Flowtime { .. })
+    }
+    
+    /// The current or pending step of a custom sequence
+    fn current_interval(&self) -> Option<&Interval> {
+        match &self.config.mode {
+            TimerMode::Sequence(intervals) => intervals.get(self.sequence_index),
+            _ => None,
+        }
+    }
+    
+    /// Session type recorded for the current work session: its name in a custom sequence
+    fn session_type(&self) -> &str {
+        self.current_interval().map_or(PomodoroState::Work.as_str(), |interval| &interval.name)
     }
     
-    pub fn get_completed_pomodoros(&self) -> usize {
-        self.completed_pomodoros
+    /// Whether an interval is waiting out its grace countdown before starting by itself
+    pub fn auto_start_pending(&self) -> bool {
+        self.auto_start_at.is_some()
+    }
+    
+    pub fn set_task(&mut self, task: Option<String>) {
+        self.task = task.filter(|t| !t.trim().is_empty());
+    }
+    
+    pub fn set_project(&mut self, project: Option<String>) {
+        self.project = project.filter(|p| !p.trim().is_empty());
+    }
+    
+    pub fn status(&self) -> PomodoroStatus {
+        PomodoroStatus {
+            state: self.state,
+            prev_state: self.prev_state,
+            remaining_seconds: self.remaining_seconds,
+            duration_seconds: self.interval_seconds(self.prev_state.unwrap_or(self.state)),
+            completed_pomodoros: self.completed_pomodoros,
+            long_break_after: self.config.long_break_after,
+            project: self.project.clone(),
+            task: self.task.clone(),
+            // Rounded up, so the countdown reads 0 only as the interval starts
+            auto_start_in: self.auto_start_at.map(|at| {
+                let millis = at.signed_duration_since(Local::now()).num_milliseconds().max(0);
+                (millis + 999) / 1000
+            }),
+            extensions_left: self.config.max_extensions.map(|max| max.saturating_sub(self.cycle_extensions)),
+            overtime: self.config.overtime,
+            strict_phrase: self.config.strict_phrase.clone(),
+            break_fraction: match self.config.mode {
+                TimerMode::Flowtime { break_fraction } => Some(break_fraction),
+                _ => None,
+            },
+            sequence: match &self.config.mode {
+                TimerMode::Sequence(intervals) => self.current_interval().map(|interval| SequenceStep {
+                    name: interval.name.clone(),
+                    label: interval.label(),
+                    step: self.sequence_index + 1,
+                    steps: intervals.len(),
+                }),
+                _ => None,
+            },
+            session_id: self.current_session_id,
+        }
     }
     
     pub fn start(&mut self) -> Result<(), PomodoroError> {
+        // Starting by hand skips what's left of an auto-start countdown
+        self.auto_start_at = None;
+        
         match self.state {
             PomodoroState::Idle => {
                 self.transition_to_work()
@@ -105,16 +621,27 @@ impl Pomodoro {
             PomodoroState::Paused => {
                 // Resume from paused state using the saved previous state
                 if let Some(prev_state) = self.prev_state {
+                    if prev_state == PomodoroState::Work {
+                        match self.current_session_id {
+                            // Resuming a paused work session ends its pause
+                            Some(session_id) => self.database.end_pause(session_id)?,
+                            // Resuming into a work session with no open record (after a break or a restart)
+                            None => {
+                                let session_id = self.database.start_session(
+                                    self.session_type(),
+                                    self.remaining_seconds,
+                                )?;
+                                self.current_session_id = Some(session_id);
+                                self.overtime_notices = 0;
+                            }
+                        }
+                    }
+                    
                     // Restore the saved state
                     self.state = prev_state;
                     
                     // Calculate elapsed time based on the correct duration for the state we're resuming
-                    let duration_seconds = match prev_state {
-                        PomodoroState::Work => self.config.work_duration.num_seconds(),
-                        PomodoroState::ShortBreak => self.config.short_break_duration.num_seconds(),
-                        PomodoroState::LongBreak => self.config.long_break_duration.num_seconds(),
-                        _ => 0, // Should never happen
-                    };
+                    let duration_seconds = self.interval_seconds(prev_state);
                     
                     // Set start time to make remaining_seconds correct
                     let elapsed_seconds = duration_seconds - self.remaining_seconds;
@@ -135,15 +662,17 @@ impl Pomodoro {
     
     fn transition_to_work(&mut self) -> Result<(), PomodoroError> {
         self.state = PomodoroState::Work;
+        self.extension_seconds = 0;
         self.start_time = Some(Local::now());
-        self.remaining_seconds = self.config.work_duration.num_seconds();
+        self.remaining_seconds = self.interval_seconds(PomodoroState::Work);
         
         let session_id = self.database.start_session(
-            "work", 
-            self.config.work_duration.num_seconds()
+            self.session_type(), 
+            self.remaining_seconds
         )?;
         
         self.current_session_id = Some(session_id);
+        self.overtime_notices = 0;
         
         // Notify that a work session has started
         self.notifier.notify_with_sound("Work Session Started", "Focus time has begun!", NotificationSound::Start);
@@ -156,6 +685,25 @@ impl Pomodoro {
             return Err(PomodoroError::NotRunning);
         }
         
+        if self.config.strict_phrase.is_some() && self.state == PomodoroState::Work && self.current_session_id.is_some() {
+            return Err(PomodoroError::Strict);
+        }
+        
+        self.pause()
+    }
+    
+    /// Pause whatever is running, recording the pause of an open work session
+    fn pause(&mut self) -> Result<(), PomodoroError> {
+        // Pausing while an auto-start counts down cancels it
+        self.auto_start_at = None;
+        
+        // Record the pause first, so a database error leaves the timer running
+        if self.state == PomodoroState::Work {
+            if let Some(session_id) = self.current_session_id {
+                self.database.start_pause(session_id)?;
+            }
+        }
+        
         // Store the current state before pausing and calculate remaining time
         if self.state != PomodoroState::Paused {
             // Save the current state so we can resume to it later
@@ -168,15 +716,12 @@ impl Pomodoro {
                 None => 0,
             };
             
-            let duration = match self.state {
-                PomodoroState::Work => self.config.work_duration.num_seconds(),
-                PomodoroState::ShortBreak => self.config.short_break_duration.num_seconds(),
-                PomodoroState::LongBreak => self.config.long_break_duration.num_seconds(),
-                _ => 0,
-            };
+            let duration = self.interval_seconds(self.state);
             
             self.remaining_seconds = duration - elapsed;
-            if self.remaining_seconds < 0 {
+            // Overtime and flowtime keep counting below zero
+            let counts_up = self.state == PomodoroState::Work && (self.config.overtime || self.is_flowtime());
+            if self.remaining_seconds < 0 && !counts_up {
                 self.remaining_seconds = 0;
             }
         }
@@ -188,36 +733,236 @@ impl Pomodoro {
         Ok(())
     }
     
+    /// Apply a command from any front end (keys, signals, integrations, control socket).
+    /// `Shutdown` is handled by the owner of the timer task and is a no-op here.
+    pub fn apply(&mut self, command: &PomodoroCommand) -> Result<(), PomodoroError> {
+        match command {
+            PomodoroCommand::Start { project, task } => {
+                if project.is_some() || task.is_some() {
+                    self.set_project(project.clone());
+                    self.set_task(task.clone());
+                }
+                self.start()
+            }
+            PomodoroCommand::Stop => self.stop(),
+            PomodoroCommand::Toggle => self.toggle(),
+            PomodoroCommand::Next => self.next(),
+            PomodoroCommand::SetTask { project, task } => {
+                self.set_project(project.clone());
+                self.set_task(task.clone());
+                Ok(())
+            }
+            PomodoroCommand::Interrupt { kind, reason } => self.interrupt(*kind, reason.as_deref()),
+            PomodoroCommand::Extend { minutes } => self.extend(*minutes),
+            PomodoroCommand::Abandon { confirmation } => self.abandon(confirmation.as_deref()),
+            PomodoroCommand::AddNote { text } => self.add_note(text),
+            PomodoroCommand::Shutdown => Ok(()),
+        }
+    }
+    
+    /// Record an interruption against the open work session, running or paused.
+    /// The timer itself carries on; pausing is up to the user.
+    pub fn interrupt(&mut self, kind: InterruptionKind, reason: Option<&str>) -> Result<(), PomodoroError> {
+        let session_id = self.current_session_id.ok_or(PomodoroError::NotWorking)?;
+        let reason = reason.map(str::trim).filter(|r| !r.is_empty());
+        self.database.add_interruption(session_id, kind, reason)?;
+        Ok(())
+    }
+    
+    /// Save a note against the open work session, running or paused, if there is one
+    pub fn add_note(&mut self, text: &str) -> Result<(), PomodoroError> {
+        self.database.add_note(text, self.current_session_id)?;
+        Ok(())
+    }
+    
+    /// Add time to the current interval, running or paused, and record it. Counts
+    /// against the per-cycle limit.
+    pub fn extend(&mut self, minutes: Option<u32>) -> Result<(), PomodoroError> {
+        let state = match self.state {
+            PomodoroState::Idle => return Err(PomodoroError::NotRunning),
+            PomodoroState::Paused => self.prev_state.ok_or(PomodoroError::NotRunning)?,
+            state => state,
+        };
+        if state == PomodoroState::Work && self.is_flowtime() {
+            return Err(PomodoroError::OpenEnded);
+        }
+        if let Some(max) = self.config.max_extensions {
+            if self.cycle_extensions >= max {
+                return Err(PomodoroError::ExtensionLimit(max));
+            }
+        }
+        
+        let seconds = match minutes {
+            Some(minutes) => i64::from(minutes) * 60,
+            None => self.config.extend_by.num_seconds(),
+        };
+        if seconds < 60 {
+            return Err(PomodoroError::EmptyExtension);
+        }
+        
+        // Only work sessions have a session record; breaks are logged by type alone
+        let session_id = self.current_session_id.filter(|_| state == PomodoroState::Work);
+        let interval_type = self.current_interval().map_or(state.as_str(), |interval| &interval.name);
+        self.database.add_extension(session_id, interval_type, seconds)?;
+        
+        self.extension_seconds += seconds;
+        self.cycle_extensions += 1;
+        // A running interval picks up the longer length on its next tick; this covers a paused one
+        self.remaining_seconds += seconds;
+        Ok(())
+    }
+    
+    /// Close the open work session as abandoned and line up a fresh one, paused. In strict
+    /// mode `confirmation` must match the configured phrase, ignoring case and surrounding spaces.
+    pub fn abandon(&mut self, confirmation: Option<&str>) -> Result<(), PomodoroError> {
+        if !self.work_session_open() {
+            return Err(PomodoroError::NotWorking);
+        }
+        if let Some(phrase) = &self.config.strict_phrase {
+            let confirmed = confirmation.is_some_and(|text| text.trim().eq_ignore_ascii_case(phrase.trim()));
+            if !confirmed {
+                return Err(PomodoroError::ConfirmationRequired(phrase.clone()));
+            }
+        }
+        
+        if let Some(session_id) = self.current_session_id {
+            self.database.end_session(session_id, SessionOutcome::Abandoned)?;
+            self.current_session_id = None;
+        }
+        
+        self.auto_start_at = None;
+        self.state = PomodoroState::Paused;
+        self.prepare_work();
+        self.announce_pending();
+        Ok(())
+    }
+    
+    /// Whether a work session has been started and not yet ended, running or paused
+    fn work_session_open(&self) -> bool {
+        let state = match self.state {
+            PomodoroState::Paused => self.prev_state,
+            state => Some(state),
+        };
+        state == Some(PomodoroState::Work) && self.current_session_id.is_some()
+    }
+    
+    /// Pause when running, resume when paused, start when idle. During an auto-start
+    /// countdown the interval already counts as running, so this cancels it.
+    pub fn toggle(&mut self) -> Result<(), PomodoroError> {
+        if self.auto_start_pending() {
+            return self.stop();
+        }
+        
+        match self.state {
+            PomodoroState::Idle | PomodoroState::Paused => self.start(),
+            _ => self.stop(),
+        }
+    }
+    
+    /// Capture the persistable parts of the timer
+    pub fn snapshot(&self) -> TimerSnapshot {
+        TimerSnapshot {
+            state: self.state.as_str().to_string(),
+            prev_state: self.prev_state.map(|s| s.as_str().to_string()),
+            remaining_seconds: self.remaining_seconds,
+            completed_pomodoros: self.completed_pomodoros as i64,
+            session_id: self.current_session_id,
+            task: self.task.clone(),
+            sequence_index: self.sequence_index as i64,
+            saved_at: Local::now(),
+        }
+    }
+    
+    /// Restore a snapshot saved by `shutdown`. The timer always comes back paused,
+    /// so nothing runs until the user resumes it.
+    pub fn restore(&mut self, snapshot: &TimerSnapshot) {
+        let state = PomodoroState::from_name(&snapshot.state).unwrap_or(PomodoroState::Idle);
+        let prev_state = snapshot.prev_state.as_deref().and_then(PomodoroState::from_name);
+        
+        self.completed_pomodoros = snapshot.completed_pomodoros.max(0) as usize;
+        // The sequence may have been shortened since, in which case it starts over
+        if let TimerMode::Sequence(intervals) = &self.config.mode {
+            let index = snapshot.sequence_index.max(0) as usize;
+            self.sequence_index = if index < intervals.len() { index } else { 0 };
+        }
+        if self.task.is_none() {
+            self.task = snapshot.task.clone();
+        }
+        
+        let resume_state = match state {
+            PomodoroState::Idle => return,
+            PomodoroState::Paused => prev_state,
+            running => Some(running),
+        };
+        
+        if let Some(resume_state) = resume_state {
+            self.state = PomodoroState::Paused;
+            self.prev_state = Some(resume_state);
+            self.remaining_seconds = snapshot.remaining_seconds.max(0);
+            self.current_session_id = snapshot.session_id;
+            // A flowtime break keeps the length it was given
+            self.earned_break_seconds = self.remaining_seconds;
+            // Time left beyond the usual length can only have come from extensions
+            self.extension_seconds = (self.remaining_seconds - self.interval_seconds(resume_state)).max(0);
+        }
+    }
+    
+    /// Pause the timer, close any in-progress session as abandoned (or completed, if it's in
+    /// overtime or counting up in flowtime) and persist the timer state. Used for every exit path: quit key, Ctrl-C and
+    /// termination signals. In strict mode a work session can only be abandoned with the
+    /// confirmation phrase, so it's left open and paused instead, and resumes on the next run.
+    pub fn shutdown(&mut self) -> Result<(), PomodoroError> {
+        // A pomodoro in overtime is already done, and a flowtime session ends whenever the
+        // user stops, so finish it as `next` would, but without announcing the break
+        let status = self.status();
+        if (status.is_overtime() || status.is_open_ended()) && self.current_session_id.is_some() {
+            self.move_on()?;
+        }
+        
+        // Close the session before pausing, so quitting isn't recorded as a pause
+        let keep_open = self.config.strict_phrase.is_some() && self.work_session_open();
+        if !keep_open {
+            if let Some(session_id) = self.current_session_id.take() {
+                self.database.end_session(session_id, SessionOutcome::Abandoned)?;
+            }
+        }
+        
+        if self.state != PomodoroState::Idle && self.state != PomodoroState::Paused {
+            self.pause()?;
+        }
+        
+        self.database.save_timer_state(&self.snapshot())?;
+        Ok(())
+    }
+    
     pub fn next(&mut self) -> Result<(), PomodoroError> {
+        // Only a work session that has run out can be left in strict mode
+        if self.config.strict_phrase.is_some() && self.work_session_open() && self.remaining_seconds > 0 {
+            return Err(PomodoroError::Strict);
+        }
+        
+        self.move_on()?;
+        self.announce_pending();
+        Ok(())
+    }
+    
+    /// Close the current interval and line up the next one, paused, without telling the user
+    fn move_on(&mut self) -> Result<(), PomodoroError> {
+        // Skipping lines up the interval after the pending one, which waits for you
+        self.auto_start_at = None;
+        
         match self.state {
             PomodoroState::Work => {
-                // Complete the current work session
-                if let Some(session_id) = self.current_session_id.take() {
-                    // Use the Result from complete_session directly
-                    self.database.complete_session(session_id)?
-                }
-                
-                self.completed_pomodoros += 1;
+                let completed = self.end_work_session()?;
                 
-                // Determine which break to take but don't start it automatically
-                if self.completed_pomodoros % self.config.long_break_after == 0 {
-                    self.state = PomodoroState::Paused;
-                    self.prev_state = Some(PomodoroState::LongBreak);
-                    self.remaining_seconds = self.config.long_break_duration.num_seconds();
-                    self.notifier.notify_with_sound("Long Break Ready", "Long break is ready!", NotificationSound::WorkDone);
-                } else {
-                    self.state = PomodoroState::Paused;
-                    self.prev_state = Some(PomodoroState::ShortBreak);
-                    self.remaining_seconds = self.config.short_break_duration.num_seconds();
-                    self.notifier.notify_with_sound("Short Break Ready", "Short break is ready!", NotificationSound::WorkDone);
-                }
+                // Set up the break but don't start it automatically
+                self.state = PomodoroState::Paused;
+                self.prepare_next(PomodoroState::Work, completed);
             },
-            PomodoroState::ShortBreak | PomodoroState::LongBreak => {
+            finished @ (PomodoroState::ShortBreak | PomodoroState::LongBreak) => {
                 // Prepare for work session but don't start it automatically
                 self.state = PomodoroState::Paused;
-                self.prev_state = Some(PomodoroState::Work);
-                self.remaining_seconds = self.config.work_duration.num_seconds();
-                self.notifier.notify_with_sound("Work Session Ready", "Work session is ready!", NotificationSound::BreakDone);
+                self.prepare_next(finished, false);
             },
             PomodoroState::Paused => {
                 // If paused, determine what the next state should be
@@ -225,44 +970,24 @@ impl Pomodoro {
                     match prev_state {
                         PomodoroState::Work => {
                             // We were paused in a work session, so next would be a break
-                            if let Some(session_id) = self.current_session_id.take() {
-                                self.database.complete_session(session_id)?;
-                            }
-                            
-                            self.completed_pomodoros += 1;
-                            
-                            // Set up the break type but don't start it
-                            if self.completed_pomodoros % self.config.long_break_after == 0 {
-                                self.prev_state = Some(PomodoroState::LongBreak);
-                                self.remaining_seconds = self.config.long_break_duration.num_seconds();
-                                self.notifier.notify_with_sound("Long Break Ready", "Long break is ready!", NotificationSound::WorkDone);
-                            } else {
-                                self.prev_state = Some(PomodoroState::ShortBreak);
-                                self.remaining_seconds = self.config.short_break_duration.num_seconds();
-                                self.notifier.notify_with_sound("Short Break Ready", "Short break is ready!", NotificationSound::WorkDone);
-                            }
+                            let completed = self.end_work_session()?;
+                            self.prepare_next(PomodoroState::Work, completed);
                         },
                         PomodoroState::ShortBreak | PomodoroState::LongBreak => {
                             // We were paused in a break, so next would be work
-                            self.prev_state = Some(PomodoroState::Work);
-                            self.remaining_seconds = self.config.work_duration.num_seconds();
-                            self.notifier.notify_with_sound("Work Session Ready", "Work session is ready!", NotificationSound::BreakDone);
+                            self.prepare_next(prev_state, false);
                         },
                         _ => {}
                     }
                 } else {
                     // If we don't know what state we were in, set up for work session
-                    self.prev_state = Some(PomodoroState::Work);
-                    self.remaining_seconds = self.config.work_duration.num_seconds();
-                    self.notifier.notify_with_sound("Work Session Ready", "Work session is ready!", NotificationSound::BreakDone);
+                    self.prepare_work();
                 }
             },
             PomodoroState::Idle => {
                 // From idle, set up for work session but don't start it
                 self.state = PomodoroState::Paused;
-                self.prev_state = Some(PomodoroState::Work);
-                self.remaining_seconds = self.config.work_duration.num_seconds();
-                self.notifier.notify_with_sound("Work Session Ready", "Work session is ready!", NotificationSound::BreakDone);
+                self.prepare_work();
             },
         }
         
@@ -270,9 +995,148 @@ impl Pomodoro {
         Ok(())
     }
     
+    /// Close the open work session when moving on to a break. It counts as completed only
+    /// if the timer had run out, with any overtime recorded; otherwise it was skipped, or
+    /// interrupted if interruptions were logged during it. A flowtime session always
+    /// completes, stored with the time actually worked. Returns whether it was completed.
+    fn end_work_session(&mut self) -> Result<bool, PomodoroError> {
+        let Some(session_id) = self.current_session_id.take() else {
+            // Skipping a work session that never started
+            return Ok(false);
+        };
+        
+        if self.is_flowtime() {
+            // A flowtime session lasts as long as the user kept going, so store that as its length
+            self.database.set_session_duration(session_id, (-self.remaining_seconds).max(0))?;
+        } else if self.remaining_seconds < 0 {
+            self.database.record_overtime(session_id, -self.remaining_seconds)?;
+        }
+        
+        let outcome = if self.remaining_seconds <= 0 {
+            SessionOutcome::Completed
+        } else if self.database.was_interrupted(session_id)? {
+            SessionOutcome::Interrupted
+        } else {
+            SessionOutcome::Skipped
+        };
+        self.database.end_session(session_id, outcome)?;
+        
+        Ok(outcome == SessionOutcome::Completed)
+    }
+    
+    /// Line up the interval that follows `finished`, paused: the next step of a custom
+    /// sequence, otherwise a break after work and work after a break
+    fn prepare_next(&mut self, finished: PomodoroState, completed: bool) {
+        let TimerMode::Sequence(intervals) = &self.config.mode else {
+            if finished == PomodoroState::Work {
+                self.prepare_break(completed);
+            } else {
+                self.prepare_work();
+            }
+            return;
+        };
+        
+        let index = (self.sequence_index + 1) % intervals.len();
+        if completed {
+            self.completed_pomodoros += 1;
+        }
+        // Going round the sequence again starts a new cycle for the extension limit
+        if index == 0 {
+            self.cycle_extensions = 0;
+        }
+        self.prepare_interval(index);
+    }
+    
+    /// Line up step `index` of the custom sequence, paused
+    fn prepare_interval(&mut self, index: usize) {
+        self.sequence_index = index;
+        let Some(state) = self.current_interval().map(|interval| interval.state) else {
+            return;
+        };
+        
+        self.prev_state = Some(state);
+        self.extension_seconds = 0;
+        self.remaining_seconds = self.interval_seconds(state);
+    }
+    
+    /// Tell the user which interval `prepare_next` or `prepare_work` lined up
+    fn announce_pending(&self) {
+        let Some(state) = self.prev_state.filter(|_| self.state == PomodoroState::Paused) else {
+            return;
+        };
+        let sound = if state == PomodoroState::Work { NotificationSound::BreakDone } else { NotificationSound::WorkDone };
+        
+        if let Some(interval) = self.current_interval() {
+            let label = match state {
+                PomodoroState::Work if interval.name == state.as_str() => "Work Session".to_string(),
+                _ => interval.label(),
+            };
+            // "Deep Work Ready", "Deep work is ready!"
+            let mut chars = label.chars();
+            let sentence: String = chars.next().into_iter().chain(chars.flat_map(char::to_lowercase)).collect();
+            self.notifier.notify_with_sound(&format!("{} Ready", label), &format!("{} is ready!", sentence), sound);
+            return;
+        }
+        
+        match state {
+            PomodoroState::Work => self.notifier.notify_with_sound("Work Session Ready", "Work session is ready!", sound),
+            PomodoroState::ShortBreak if self.is_flowtime() => {
+                let message = format!("You've earned a {} minute break", (self.earned_break_seconds + 59) / 60);
+                self.notifier.notify_with_sound("Short Break Ready", &message, sound);
+            }
+            PomodoroState::ShortBreak => self.notifier.notify_with_sound("Short Break Ready", "Short break is ready!", sound),
+            PomodoroState::LongBreak => self.notifier.notify_with_sound("Long Break Ready", "Long break is ready!", sound),
+            PomodoroState::Idle | PomodoroState::Paused => {}
+        }
+    }
+    
+    /// Line up a work session, paused. In a custom sequence, the pending step is lined up again.
+    fn prepare_work(&mut self) {
+        if matches!(self.config.mode, TimerMode::Sequence(_)) {
+            self.prepare_interval(self.sequence_index);
+            return;
+        }
+        self.prev_state = Some(PomodoroState::Work);
+        self.extension_seconds = 0;
+        self.remaining_seconds = self.interval_seconds(PomodoroState::Work);
+    }
+    
+    /// Line up the break that follows a work session, paused. Only a completed
+    /// pomodoro counts towards the long break.
+    fn prepare_break(&mut self, completed: bool) {
+        if completed {
+            self.completed_pomodoros += 1;
+        }
+        self.extension_seconds = 0;
+        
+        // Every flowtime break is a short one, earned in proportion to the time worked. With no
+        // long breaks, each break starts a new cycle for the extension limit.
+        if let TimerMode::Flowtime { break_fraction } = self.config.mode {
+            self.cycle_extensions = 0;
+            self.earned_break_seconds = flowtime_break_seconds(-self.remaining_seconds, break_fraction);
+            self.prev_state = Some(PomodoroState::ShortBreak);
+            self.remaining_seconds = self.earned_break_seconds;
+            return;
+        }
+        
+        if completed && self.completed_pomodoros.is_multiple_of(self.config.long_break_after) {
+            // The long break closes the cycle, so the extension limit starts over
+            self.cycle_extensions = 0;
+            self.prev_state = Some(PomodoroState::LongBreak);
+            self.remaining_seconds = self.config.long_break_duration.num_seconds();
+        } else {
+            self.prev_state = Some(PomodoroState::ShortBreak);
+            self.remaining_seconds = self.config.short_break_duration.num_seconds();
+        }
+    }
+    
     pub fn update(&mut self) {
-        // Make sure we don't update if we're already paused
-        if self.state == PomodoroState::Idle || self.state == PomodoroState::Paused {
+        // A paused timer only moves when an auto-start is due
+        if self.state == PomodoroState::Paused {
+            self.check_auto_start();
+            return;
+        }
+        if self.state == PomodoroState::Idle {
             return;
         }
         
@@ -282,51 +1146,96 @@ impl Pomodoro {
             None => 0,
         };
         
-        let duration = match self.state {
-            PomodoroState::Work => self.config.work_duration.num_seconds(),
-            PomodoroState::ShortBreak => self.config.short_break_duration.num_seconds(),
-            PomodoroState::LongBreak => self.config.long_break_duration.num_seconds(),
-            _ => 0,
-        };
+        let duration = self.interval_seconds(self.state);
         
         self.remaining_seconds = duration - elapsed;
         
+        // A flowtime work session runs until the user moves on
+        if self.state == PomodoroState::Work && self.is_flowtime() {
+            return;
+        }
+        
         // Check if the timer has expired
         if self.remaining_seconds <= 0 {
+            // In overtime mode a work session carries on until the user moves on
+            if self.config.overtime && self.state == PomodoroState::Work {
+                self.notify_overtime();
+                return;
+            }
+            
+            let finished = self.state;
             match self.state {
                 PomodoroState::Work => {
                     // Complete the work session with proper error handling
                     if let Some(session_id) = self.current_session_id.take() {
-                        if let Err(e) = self.database.complete_session(session_id) {
-                            eprintln!("Failed to complete session {}: {}", session_id, e);
+                        if let Err(e) = self.database.end_session(session_id, SessionOutcome::Completed) {
+                            self.log(format!("Failed to complete session {}: {}", session_id, e));
                         }
                     }
                     
-                    self.completed_pomodoros += 1;
-                    
                     // Set up for a break but don't start it automatically
                     self.state = PomodoroState::Paused;
-                    if self.completed_pomodoros % self.config.long_break_after == 0 {
-                        self.prev_state = Some(PomodoroState::LongBreak);
-                        self.remaining_seconds = self.config.long_break_duration.num_seconds();
-                        self.notifier.notify_with_sound("Long Break Ready", "Long break is ready!", NotificationSound::WorkDone);
-                    } else {
-                        self.prev_state = Some(PomodoroState::ShortBreak);
-                        self.remaining_seconds = self.config.short_break_duration.num_seconds();
-                        self.notifier.notify_with_sound("Short Break Ready", "Short break is ready!", NotificationSound::WorkDone);
-                    }
+                    self.prepare_next(finished, true);
                 },
                 PomodoroState::ShortBreak | PomodoroState::LongBreak => {
                     // Set up for work session but don't start it automatically
                     self.state = PomodoroState::Paused;
-                    self.prev_state = Some(PomodoroState::Work);
-                    self.remaining_seconds = self.config.work_duration.num_seconds();
-                    self.notifier.notify_with_sound("Work Session Ready", "Work session is ready!", NotificationSound::BreakDone);
+                    self.prepare_next(finished, false);
                 },
                 _ => {}
             }
             
-            // Don't set start_time as we're not starting automatically
+            self.announce_pending();
+            self.schedule_auto_start(finished);
+        }
+    }
+    
+    /// Tell the user the pomodoro is done as overtime begins, then remind them every
+    /// `overtime_reminder` for as long as it lasts
+    fn notify_overtime(&mut self) {
+        let overtime = -self.remaining_seconds;
+        if self.overtime_notices == 0 {
+            self.notifier.notify_with_sound("Pomodoro Complete", "Time's up! Keep going, or move on to your break.", NotificationSound::WorkDone);
+            self.overtime_notices = 1;
+            return;
+        }
+        
+        let reminder = self.config.overtime_reminder.num_seconds();
+        if reminder > 0 && overtime >= reminder * self.overtime_notices {
+            let minutes = overtime / 60;
+            self.notifier.notify_with_sound(
+                "Overtime",
+                &format!("{} minute{} past the end of this pomodoro", minutes, if minutes == 1 { "" } else { "s" }),
+                NotificationSound::WorkDone,
+            );
+            self.overtime_notices += 1;
+        }
+    }
+    
+    /// Begin the grace countdown for the interval lined up after `finished`, if the
+    /// auto-start rules cover it. Without a grace period it starts straight away.
+    fn schedule_auto_start(&mut self, finished: PomodoroState) {
+        let Some(next) = self.prev_state else {
+            return;
+        };
+        if self.config.auto_start.applies(finished, next) {
+            self.auto_start_at = Some(Local::now() + self.config.auto_start_grace);
+            self.check_auto_start();
+        }
+    }
+    
+    /// Start the paused interval once its auto-start countdown has run out
+    fn check_auto_start(&mut self) {
+        let Some(at) = self.auto_start_at else {
+            return;
+        };
+        if Local::now() < at {
+            return;
+        }
+        
+        // `start` clears the countdown first, so a failure leaves the interval paused
+        if let Err(e) = self.start() {
+            self.log(format!("Failed to start the next interval automatically: {}", e));
         }
     }
 }
@@ -334,6 +1243,7 @@ impl Pomodoro {
 pub async fn run_pomodoro_timer(
     pomodoro: Arc<Mutex<Pomodoro>>,
     mut command_rx: mpsc::Receiver<PomodoroCommand>,
+    status_tx: watch::Sender<PomodoroStatus>,
 ) {
     // Create timer intervals - regular and slow for static states
     let mut regular_interval = time::interval(time::Duration::from_secs(1));
@@ -344,12 +1254,14 @@ pub async fn run_pomodoro_timer(
     
     loop {
         // Determine which interval to use based on state
-        let current_state = {
+        let (current_state, auto_start_pending) = {
             let pomodoro_lock = pomodoro.lock().unwrap();
-            pomodoro_lock.get_state()
+            (pomodoro_lock.get_state(), pomodoro_lock.auto_start_pending())
         };
         
-        let is_static = current_state == PomodoroState::Paused || current_state == PomodoroState::Idle;
+        // A pending auto-start counts down like a running interval
+        let is_static = (current_state == PomodoroState::Paused || current_state == PomodoroState::Idle)
+            && !auto_start_pending;
         
         // If state changed between static/active, log it (useful for debugging)
         if is_static != was_static {
@@ -368,23 +1280,212 @@ pub async fn run_pomodoro_timer(
             
             cmd = command_rx.recv() => {
                 match cmd {
-                    Some(PomodoroCommand::Start) => {
-                        let mut pomodoro = pomodoro.lock().unwrap();
-                        let _ = pomodoro.start();
-                    }
-                    Some(PomodoroCommand::Stop) => {
-                        let mut pomodoro = pomodoro.lock().unwrap();
-                        let _ = pomodoro.stop();
-                    }
-                    Some(PomodoroCommand::Next) => {
-                        let mut pomodoro = pomodoro.lock().unwrap();
-                        let _ = pomodoro.next();
-                    }
                     Some(PomodoroCommand::Shutdown) | None => {
                         break;
                     }
+                    Some(command) => {
+                        // Senders that need the outcome apply their commands directly; these come
+                        // from signals, integrations and keys, so report failures where the user sees them
+                        let mut pomodoro = pomodoro.lock().unwrap();
+                        if let Err(e) = pomodoro.apply(&command) {
+                            pomodoro.log(format!("Timer command failed: {}", e));
+                        }
+                    }
                 }
             }
         }
+        
+        // Publish the latest status so observers (MQTT, etc.) see every transition
+        let status = pomodoro.lock().unwrap().status();
+        status_tx.send_if_modified(|current| {
+            if *current != status {
+                *current = status;
+                true
+            } else {
+                false
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Keeps the titles of the notifications it was asked to show
+    #[derive(Default)]
+    struct RecordingNotifier {
+        titles: Mutex<Vec<String>>,
+    }
+
+    impl RecordingNotifier {
+        fn take(&self) -> Vec<String> {
+            std::mem::take(&mut self.titles.lock().unwrap())
+        }
+    }
+
+    impl Notifier for RecordingNotifier {
+        fn notify(&self, title: &str, _message: &str) {
+            self.titles.lock().unwrap().push(title.to_string());
+        }
+    }
+
+    /// A timer on an in-memory database, with its notifications and the receiving end of its log
+    fn pomodoro(config: PomodoroConfig) -> (Pomodoro, Arc<RecordingNotifier>, mpsc::UnboundedReceiver<String>) {
+        let database = Arc::new(Database::new(":memory:").unwrap());
+        let notifier = Arc::new(RecordingNotifier::default());
+        let (log_tx, log_rx) = mpsc::unbounded_channel();
+        (Pomodoro::new(config, database, notifier.clone(), log_tx), notifier, log_rx)
+    }
+
+    /// Pretend the running interval started `seconds` ago
+    fn run_for(pomodoro: &mut Pomodoro, seconds: i64) {
+        pomodoro.start_time = Some(Local::now() - Duration::seconds(seconds));
+        pomodoro.update();
+    }
+
+    #[test]
+    fn failed_auto_start_stays_paused_and_is_logged() {
+        let (mut pomodoro, _, mut log_rx) = pomodoro(PomodoroConfig::default());
+        pomodoro.next().unwrap();
+        pomodoro.auto_start_at = Some(Local::now() - Duration::seconds(1));
+        // Starting the work session can't record it any more
+        pomodoro.database.execute_batch("DROP TABLE pomodoro_sessions").unwrap();
+
+        pomodoro.update();
+
+        let status = pomodoro.status();
+        assert_eq!(status.state, PomodoroState::Paused);
+        assert_eq!(status.prev_state, Some(PomodoroState::Work));
+        assert_eq!(status.auto_start_in, None);
+        assert!(!pomodoro.auto_start_pending());
+        assert!(log_rx.try_recv().unwrap().starts_with("Failed to start the next interval automatically"));
+    }
+
+    #[test]
+    fn next_announces_the_break() {
+        let (mut pomodoro, notifier, _) = pomodoro(PomodoroConfig::default());
+        pomodoro.start().unwrap();
+        notifier.take();
+
+        pomodoro.next().unwrap();
+
+        assert_eq!(pomodoro.status().prev_state, Some(PomodoroState::ShortBreak));
+        assert_eq!(notifier.take(), vec!["Short Break Ready"]);
+    }
+
+    #[test]
+    fn quitting_in_overtime_completes_the_session_quietly() {
+        let config = PomodoroConfig { overtime: true, ..PomodoroConfig::default() };
+        let (mut pomodoro, notifier, _) = pomodoro(config);
+        pomodoro.start().unwrap();
+        run_for(&mut pomodoro, 26 * 60);
+        assert!(pomodoro.status().is_overtime());
+        notifier.take();
+
+        pomodoro.shutdown().unwrap();
+
+        let status = pomodoro.status();
+        assert_eq!(status.state, PomodoroState::Paused);
+        assert_eq!(status.prev_state, Some(PomodoroState::ShortBreak));
+        assert_eq!(status.completed_pomodoros, 1);
+        assert!(notifier.take().is_empty());
+    }
+
+    #[test]
+    fn flowtime_breaks_start_a_new_extension_cycle() {
+        let config = PomodoroConfig {
+            mode: TimerMode::Flowtime { break_fraction: 0.2 },
+            max_extensions: Some(1),
+            ..PomodoroConfig::default()
+        };
+        let (mut pomodoro, _, _) = pomodoro(config);
+
+        for _ in 0..3 {
+            pomodoro.start().unwrap();
+            run_for(&mut pomodoro, 30 * 60);
+            pomodoro.next().unwrap();
+            assert_eq!(pomodoro.status().extensions_left, Some(1));
+            pomodoro.extend(Some(2)).unwrap();
+            assert!(matches!(pomodoro.extend(Some(2)), Err(PomodoroError::ExtensionLimit(1))));
+            pomodoro.next().unwrap();
+        }
+    }
+    
+    fn sequence(text: &str) -> Vec<Interval> {
+        text.split(',').map(|interval| Interval::parse(interval).unwrap()).collect()
+    }
+    
+    #[test]
+    fn intervals_round_trip_through_their_text_form() {
+        // `--sequence` is handed to the daemon and the systemd unit in this form
+        for text in ["work:50:deep_work", "work:25", "short_break:5", "long_break:20:walk"] {
+            let interval = Interval::parse(text).unwrap();
+            assert_eq!(interval.to_string(), text);
+            assert_eq!(Interval::parse(&interval.to_string()).unwrap(), interval);
+        }
+        let interval = Interval::parse(" break : 10 : Stretch ").unwrap();
+        assert_eq!(interval, Interval { state: PomodoroState::ShortBreak, name: "stretch".into(), duration: Duration::minutes(10) });
+        assert_eq!(Interval::parse(&interval.to_string()).unwrap(), interval);
+    }
+    
+    #[test]
+    fn intervals_cannot_borrow_another_kind_of_name() {
+        assert!(Interval::parse("work:25:work").is_ok());
+        assert!(Interval::parse("long_break:20:long_break").is_ok());
+        for text in ["work:25:short_break", "break:5:work", "long_break:20:paused", "work:25:deep-work", "work:25:", "rest:5", "work:0", "work", "work:25:a:b"] {
+            assert!(Interval::parse(text).is_err(), "{}", text);
+        }
+    }
+    
+    #[test]
+    fn sequences_wrap_around() {
+        let config = PomodoroConfig {
+            mode: TimerMode::Sequence(sequence("work:50:deep_work,break:10,work:25,long_break:20")),
+            ..PomodoroConfig::default()
+        };
+        let (mut pomodoro, _, _) = pomodoro(config);
+        pomodoro.start().unwrap();
+        
+        let mut steps = Vec::new();
+        for _ in 0..5 {
+            let step = pomodoro.status().sequence.unwrap();
+            steps.push((step.step, step.name));
+            // Running out lines up the next step
+            run_for(&mut pomodoro, 60 * 60);
+            pomodoro.start().unwrap();
+        }
+        
+        let names = ["deep_work", "short_break", "work", "long_break", "deep_work"];
+        assert_eq!(steps, names.iter().enumerate().map(|(i, name)| (i % 4 + 1, name.to_string())).collect::<Vec<_>>());
+        assert_eq!(pomodoro.status().completed_pomodoros, 3);
+        assert_eq!(pomodoro.status().state, PomodoroState::ShortBreak);
+    }
+    
+    #[test]
+    fn restore_picks_up_the_sequence_where_it_stopped() {
+        let config = PomodoroConfig {
+            mode: TimerMode::Sequence(sequence("work:50:deep_work,break:10,work:25")),
+            ..PomodoroConfig::default()
+        };
+        let (mut pomodoro, _, _) = pomodoro(config.clone());
+        pomodoro.start().unwrap();
+        run_for(&mut pomodoro, 60 * 60);
+        pomodoro.next().unwrap();
+        let mut snapshot = pomodoro.snapshot();
+        assert_eq!(snapshot.sequence_index, 2);
+        
+        let (mut restored, _, _) = self::pomodoro(config.clone());
+        restored.restore(&snapshot);
+        let status = restored.status();
+        assert_eq!(status.state, PomodoroState::Paused);
+        assert_eq!(status.sequence.unwrap().step, 3);
+        assert_eq!(status.remaining_seconds, 25 * 60);
+        
+        // A sequence shortened since starts over
+        snapshot.sequence_index = 7;
+        let (mut restored, _, _) = self::pomodoro(config);
+        restored.restore(&snapshot);
+        assert_eq!(restored.status().sequence.unwrap().step, 1);
     }
 }
@@ -0,0 +1,201 @@
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use thiserror::Error;
+
+/// Base name of the generated unit files
+pub const UNIT_NAME: &str = "pomodoro-cli";
+
+#[derive(Error, Debug)]
+pub enum ServiceError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Could not determine the systemd user unit directory (HOME is not set)")]
+    NoUnitDir,
+
+    #[error("`systemctl --user {0}` failed")]
+    Systemctl(String),
+}
+
+/// Directory systemd reads user units from
+pub fn unit_dir() -> Result<PathBuf, ServiceError> {
+    let config_home = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME").ok_or(ServiceError::NoUnitDir)?).join(".config"),
+    };
+    Ok(config_home.join("systemd").join("user"))
+}
+
+/// Quote one ExecStart argument using systemd's rules: `%` and `$` must be doubled to
+/// escape specifier and variable expansion, and arguments with spaces or quotes are double-quoted.
+fn quote_exec_arg(arg: &str) -> String {
+    let escaped = arg.replace('%', "%%").replace('$', "$$");
+    if escaped.is_empty() || escaped.contains(|c: char| c.is_whitespace() || c == '"' || c == '\\' || c == '\'') {
+        format!("\"{}\"", escaped.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        escaped
+    }
+}
+
+/// Render the service unit that runs the timer daemon
+pub fn render_service_unit(exe: &Path, daemon_args: &[OsString]) -> String {
+    let mut exec_start = quote_exec_arg(&exe.to_string_lossy());
+    for arg in daemon_args {
+        exec_start.push(' ');
+        exec_start.push_str(&quote_exec_arg(&arg.to_string_lossy()));
+    }
+
+    format!(
+        "[Unit]
+Description=Pomodoro timer
+Requires={name}.socket
+After={name}.socket
+
+[Service]
+Type=simple
+ExecStart={exec_start}
+Restart=on-failure
+StandardOutput=journal
+StandardError=journal
+SyslogIdentifier={name}
+
+[Install]
+WantedBy=default.target
+",
+        name = UNIT_NAME,
+        exec_start = exec_start,
+    )
+}
+
+/// Render the socket unit that activates the daemon when a client connects
+pub fn render_socket_unit(socket: &Path) -> String {
+    format!(
+        "[Unit]
+Description=Pomodoro timer control socket
+
+[Socket]
+ListenStream={socket}
+SocketMode=0600
+RemoveOnStop=yes
+
+[Install]
+WantedBy=sockets.target
+",
+        socket = socket.display().to_string().replace('%', "%%"),
+    )
+}
+
+fn systemctl(args: &[&str]) -> Result<(), ServiceError> {
+    let status = Command::new("systemctl").arg("--user").args(args).status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(ServiceError::Systemctl(args.join(" ")))
+    }
+}
+
+/// Write both units and enable the socket so the daemon starts on first use
+pub fn install(exe: &Path, daemon_args: &[OsString], socket: &Path) -> Result<(), ServiceError> {
+    let dir = unit_dir()?;
+    fs::create_dir_all(&dir)?;
+
+    let service_path = dir.join(format!("{}.service", UNIT_NAME));
+    let socket_path = dir.join(format!("{}.socket", UNIT_NAME));
+    fs::write(&service_path, render_service_unit(exe, daemon_args))?;
+    fs::write(&socket_path, render_socket_unit(socket))?;
+
+    println!("Wrote {}", service_path.display());
+    println!("Wrote {}", socket_path.display());
+
+    systemctl(&["daemon-reload"])?;
+    systemctl(&["enable", "--now", &format!("{}.socket", UNIT_NAME)])?;
+
+    println!("Enabled {}.socket; the timer starts on the first command.", UNIT_NAME);
+    Ok(())
+}
+
+/// Stop and disable the units, then remove them
+pub fn uninstall() -> Result<(), ServiceError> {
+    let dir = unit_dir()?;
+
+    // Units may already be stopped or disabled; keep going so the files still get removed
+    let _ = systemctl(&["disable", "--now", &format!("{}.socket", UNIT_NAME)]);
+    let _ = systemctl(&["stop", &format!("{}.service", UNIT_NAME)]);
+
+    for extension in ["service", "socket"] {
+        let path = dir.join(format!("{}.{}", UNIT_NAME, extension));
+        match fs::remove_file(&path) {
+            Ok(()) => println!("Removed {}", path.display()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    systemctl(&["daemon-reload"])
+}
+
+/// Show systemd's view of both units
+pub fn status() -> Result<(), ServiceError> {
+    // `systemctl status` exits non-zero for inactive units, which is not an error here
+    Command::new("systemctl")
+        .args(["--user", "status", "--no-pager"])
+        .arg(format!("{}.socket", UNIT_NAME))
+        .arg(format!("{}.service", UNIT_NAME))
+        .status()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<OsString> {
+        args.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn plain_arguments_are_left_alone() {
+        assert_eq!(quote_exec_arg("--database"), "--database");
+        assert_eq!(quote_exec_arg("/usr/bin/pomodoro-cli"), "/usr/bin/pomodoro-cli");
+    }
+
+    #[test]
+    fn quotes_spaces_quotes_and_empty_arguments() {
+        assert_eq!(quote_exec_arg("My Documents"), "\"My Documents\"");
+        assert_eq!(quote_exec_arg(""), "\"\"");
+        assert_eq!(quote_exec_arg("say \"hi\""), "\"say \\\"hi\\\"\"");
+        assert_eq!(quote_exec_arg("it's"), "\"it's\"");
+        assert_eq!(quote_exec_arg("C:\\tmp"), "\"C:\\\\tmp\"");
+    }
+
+    #[test]
+    fn escapes_specifiers_and_variables() {
+        assert_eq!(quote_exec_arg("100%"), "100%%");
+        assert_eq!(quote_exec_arg("$HOME"), "$$HOME");
+        assert_eq!(quote_exec_arg("${USER} 50%"), "\"$${USER} 50%%\"");
+    }
+
+    #[test]
+    fn service_unit_runs_the_daemon_with_quoted_arguments() {
+        let unit = render_service_unit(
+            Path::new("/opt/my tools/pomodoro-cli"),
+            &args(&["--database", "/home/me/50% done/$work.db", "--task", "Write report", "daemon"]),
+        );
+        assert!(unit.contains(
+            "\nExecStart=\"/opt/my tools/pomodoro-cli\" --database \"/home/me/50%% done/$$work.db\" --task \"Write report\" daemon\n"
+        ));
+        assert!(unit.contains("\nRequires=pomodoro-cli.socket\n"));
+        assert!(unit.contains("\nWantedBy=default.target\n"));
+    }
+
+    #[test]
+    fn socket_unit_listens_on_the_control_socket() {
+        let unit = render_socket_unit(Path::new("/run/user/1000/pomodoro 100%.sock"));
+        assert!(unit.contains("\nListenStream=/run/user/1000/pomodoro 100%%.sock\n"));
+        assert!(unit.contains("\nWantedBy=sockets.target\n"));
+    }
+}
@@ -0,0 +1,67 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use tokio::sync::mpsc;
+
+use crate::pomodoro::PomodoroCommand;
+
+/// Listen for process signals for the lifetime of the timer.
+///
+/// SIGINT, SIGTERM and SIGHUP raise the `shutdown` flag so the caller can close the
+/// current session and restore the terminal before exiting. SIGUSR1 toggles
+/// pause/resume and SIGUSR2 skips to the next interval.
+#[cfg(unix)]
+pub fn spawn_signal_handler(
+    cmd_tx: mpsc::Sender<PomodoroCommand>,
+    shutdown: Arc<AtomicBool>,
+) -> std::io::Result<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut interrupt = signal(SignalKind::interrupt())?;
+    let mut terminate = signal(SignalKind::terminate())?;
+    let mut hangup = signal(SignalKind::hangup())?;
+    let mut user1 = signal(SignalKind::user_defined1())?;
+    let mut user2 = signal(SignalKind::user_defined2())?;
+
+    tokio::spawn(async move {
+        loop {
+            let command = tokio::select! {
+                _ = interrupt.recv() => None,
+                _ = terminate.recv() => None,
+                _ = hangup.recv() => None,
+                _ = user1.recv() => Some(PomodoroCommand::Toggle),
+                _ = user2.recv() => Some(PomodoroCommand::Next),
+            };
+
+            match command {
+                Some(command) => {
+                    if cmd_tx.send(command).await.is_err() {
+                        // Timer has shut down
+                        break;
+                    }
+                }
+                None => {
+                    shutdown.store(true, Ordering::SeqCst);
+                    break;
+                }
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Listen for Ctrl-C on platforms without Unix signals
+#[cfg(not(unix))]
+pub fn spawn_signal_handler(
+    _cmd_tx: mpsc::Sender<PomodoroCommand>,
+    shutdown: Arc<AtomicBool>,
+) -> std::io::Result<()> {
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            shutdown.store(true, Ordering::SeqCst);
+        }
+    });
+
+    Ok(())
+}
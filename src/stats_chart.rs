@@ -1,4 +1,5 @@
-use crate::db::{PomodoroSession, StatsDaily, SessionTypeSummary};
+use crate::db::{is_work_type, InterruptionsHourly, PomodoroSession, StatsDaily, SessionTypeSummary};
+use crate::theme::Theme;
 use std::io;
 use crossterm::{
     style::{Color, Stylize},
@@ -6,189 +7,289 @@ use crossterm::{
 };
 
 const MAX_CHART_WIDTH: usize = 60;
+// Columns used by the label and value around each bar
+const CHART_MARGIN: usize = 20;
+
+/// A line of chart output made of differently colored parts.
+/// `Color::Reset` parts use the terminal's default color.
+pub type StyledLine = Vec<(String, Color)>;
+
+fn plain(text: impl Into<String>) -> StyledLine {
+    vec![(text.into(), Color::Reset)]
+}
+
+/// Terminal width; falls back to 80 columns when stdout isn't a terminal
+fn terminal_width() -> u16 {
+    terminal::size().map(|(width, _)| width).unwrap_or(80)
+}
+
+/// Width available for bars in a region `width` columns wide
+fn chart_width(width: u16) -> usize {
+    (width as usize).saturating_sub(CHART_MARGIN).min(MAX_CHART_WIDTH)
+}
+
+/// Bar of `value` scaled so that `max_value` fills `width`
+fn bar(value: i64, max_value: i64, width: usize, theme: &Theme) -> String {
+    let length = if max_value > 0 {
+        ((value.max(0) as f64 / max_value as f64) * width as f64) as usize
+    } else {
+        0
+    };
+    theme.symbols.block.repeat(length.min(width))
+}
+
+fn print_lines(lines: &[StyledLine]) {
+    for line in lines {
+        for (text, color) in line {
+            if *color == Color::Reset {
+                print!("{}", text);
+            } else {
+                print!("{}", text.as_str().with(*color));
+            }
+        }
+        println!();
+    }
+}
 
 /// Display a bar chart of session durations
-pub fn display_session_chart(sessions: &[PomodoroSession]) -> io::Result<()> {
+pub fn display_session_chart(sessions: &[PomodoroSession], theme: &Theme) -> io::Result<()> {
+    print_lines(&session_chart_lines(sessions, terminal_width(), theme));
+    Ok(())
+}
+
+/// Bar chart of session durations, laid out for a region `width` columns wide
+pub fn session_chart_lines(sessions: &[PomodoroSession], width: u16, theme: &Theme) -> Vec<StyledLine> {
+    let mut lines = Vec::new();
     if sessions.is_empty() {
-        return Ok(());
+        return lines;
     }
 
     // Only get work sessions for the chart
     let work_sessions: Vec<&PomodoroSession> = sessions.iter()
-        .filter(|s| s.session_type == "work")
+        .filter(|s| is_work_type(&s.session_type))
         .collect();
-    
+
     if work_sessions.is_empty() {
-        println!("\nNo work sessions to display in chart.");
-        return Ok(());
+        lines.push(plain(""));
+        lines.push(plain("No work sessions to display in chart."));
+        return lines;
     }
 
-    // Get terminal width
-    let (width, _) = terminal::size()?;
-    let chart_width = std::cmp::min(MAX_CHART_WIDTH, width as usize - 20);
-    
+    let chart_width = chart_width(width);
+
     // Find the maximum duration for scaling
     let max_duration = work_sessions.iter()
         .map(|s| s.duration_seconds / 60)
         .max()
         .unwrap_or(25);
-    
-    println!("\nWork Session Durations (minutes):");
-    println!("{}", "-".repeat(chart_width + 10));
-    
+
+    lines.push(plain(""));
+    lines.push(plain("Work Session Durations (minutes):"));
+    lines.push(plain("-".repeat(chart_width + 10)));
+
     // Display up to the last 10 sessions in reverse order (most recent first)
     let sessions_to_display = if work_sessions.len() > 10 {
         &work_sessions[0..10]
     } else {
         &work_sessions[..]
     };
-    
-    for (_, session) in sessions_to_display.iter().enumerate() {
+
+    for session in sessions_to_display.iter() {
         let minutes = session.duration_seconds / 60;
-        let bar_length = ((minutes as f64 / max_duration as f64) * chart_width as f64) as usize;
-        let bar = "█".repeat(bar_length);
-        
+        let bar = bar(minutes, max_duration, chart_width, theme);
+
         let color = if session.completed {
-            Color::Green
+            theme.success
         } else {
-            Color::Red
+            theme.failure
         };
-        
+
         let date_str = session.start_time.format("%m-%d %H:%M").to_string();
-        
-        print!("{:>8} ", date_str);
-        println!("{} {}", bar.with(color), minutes);
+
+        lines.push(vec![
+            (format!("{:>8} ", date_str), Color::Reset),
+            (bar, color),
+            (format!(" {}", minutes), Color::Reset),
+        ]);
     }
-    
-    println!("{}", "-".repeat(chart_width + 10));
-    Ok(())
+
+    lines.push(plain("-".repeat(chart_width + 10)));
+    lines
 }
 
 /// Display a bar chart of daily stats
-pub fn display_daily_chart(stats: &[StatsDaily]) -> io::Result<()> {
+pub fn display_daily_chart(stats: &[StatsDaily], theme: &Theme) -> io::Result<()> {
+    print_lines(&daily_chart_lines(stats, terminal_width(), theme));
+    Ok(())
+}
+
+/// Bar charts of daily stats, laid out for a region `width` columns wide
+pub fn daily_chart_lines(stats: &[StatsDaily], width: u16, theme: &Theme) -> Vec<StyledLine> {
+    let mut lines = Vec::new();
     if stats.is_empty() {
-        return Ok(());
+        return lines;
     }
 
     // Prepare data for daily work minutes chart
     let dates: Vec<String> = stats.iter().map(|s| s.date.clone()).collect();
     let minutes: Vec<i64> = stats.iter().map(|s| s.total_work_minutes).collect();
     let max_minutes = *minutes.iter().max().unwrap_or(&60);
-    
-    // Display work minutes chart
-    draw_horizontal_bar_chart(&dates, &minutes, max_minutes, "Daily Work Minutes", Color::Cyan)?;
-    
+
+    // Work minutes chart
+    lines.extend(horizontal_bar_chart_lines(&dates, &minutes, max_minutes, "Daily Focus Minutes", theme.chart[0], width, theme));
+
     // Prepare data for session counts chart
     let sessions: Vec<i64> = stats.iter().map(|s| s.work_sessions).collect();
     let max_sessions = *sessions.iter().max().unwrap_or(&10);
-    
-    // Display sessions chart
-    draw_horizontal_bar_chart(&dates, &sessions, max_sessions, "Daily Work Sessions", Color::Yellow)?;
-    
+
+    // Sessions chart
+    lines.extend(horizontal_bar_chart_lines(&dates, &sessions, max_sessions, "Daily Work Sessions", theme.chart[1], width, theme));
+
     // Prepare data for completion rate chart
     let completion_rates: Vec<i64> = stats.iter()
         .map(|s| (s.completion_rate * 100.0).round() as i64)
         .collect();
-    
-    // Display completion rate chart
-    draw_horizontal_bar_chart(&dates, &completion_rates, 100, "Completion Rates (%)", Color::Green)?;
-    
-    Ok(())
+
+    // Completion rate chart
+    lines.extend(horizontal_bar_chart_lines(&dates, &completion_rates, 100, "Completion Rates (%)", theme.chart[2], width, theme));
+
+    lines
 }
 
 /// Display a bar chart of session type stats
-pub fn display_type_chart(stats: &[SessionTypeSummary]) -> io::Result<()> {
+pub fn display_type_chart(stats: &[SessionTypeSummary], theme: &Theme) -> io::Result<()> {
+    print_lines(&type_chart_lines(stats, terminal_width(), theme));
+    Ok(())
+}
+
+/// Bar charts of session type stats, laid out for a region `width` columns wide
+pub fn type_chart_lines(stats: &[SessionTypeSummary], width: u16, theme: &Theme) -> Vec<StyledLine> {
+    let mut lines = Vec::new();
     if stats.is_empty() {
-        return Ok(());
+        return lines;
     }
 
-    // Get terminal width
-    let (width, _) = terminal::size()?;
-    let chart_width = std::cmp::min(MAX_CHART_WIDTH, width as usize - 20);
-    
+    let chart_width = chart_width(width);
+
     // Find maximum values for scaling
     let max_count = stats.iter()
         .map(|s| s.count)
         .max()
         .unwrap_or(10);
-    
+
     let max_minutes = stats.iter()
         .map(|s| s.total_minutes)
         .max()
         .unwrap_or(60);
-    
-    println!("\nSession Counts by Type:");
-    println!("{}", "-".repeat(chart_width + 10));
-    
+
+    lines.push(plain(""));
+    lines.push(plain("Session Counts by Type:"));
+    lines.push(plain("-".repeat(chart_width + 10)));
+
     for stat in stats {
-        let bar_length = ((stat.count as f64 / max_count as f64) * chart_width as f64) as usize;
-        let bar = "█".repeat(bar_length);
-        
-        // Different colors for different session types
-        let color = match stat.session_type.as_str() {
-            "work" => Color::Red,
-            "short_break" => Color::Green,
-            "long_break" => Color::Blue,
-            _ => Color::White,
-        };
-        
-        print!("{:>12} ", stat.session_type);
-        println!("{} {}", bar.with(color), stat.count);
+        lines.push(vec![
+            (format!("{:>12} ", stat.session_type), Color::Reset),
+            (bar(stat.count, max_count, chart_width, theme), theme.session_type_color(&stat.session_type)),
+            (format!(" {}", stat.count), Color::Reset),
+        ]);
     }
-    
-    println!("{}", "-".repeat(chart_width + 10));
-    
+
+    lines.push(plain("-".repeat(chart_width + 10)));
+
     // Now create a chart showing minutes by type
-    println!("\nTotal Minutes by Type:");
-    println!("{}", "-".repeat(chart_width + 10));
-    
+    lines.push(plain(""));
+    lines.push(plain("Total Minutes by Type:"));
+    lines.push(plain("-".repeat(chart_width + 10)));
+
     for stat in stats {
-        let bar_length = ((stat.total_minutes as f64 / max_minutes as f64) * chart_width as f64) as usize;
-        let bar = "█".repeat(bar_length);
-        
-        // Different colors for different session types
-        let color = match stat.session_type.as_str() {
-            "work" => Color::Red,
-            "short_break" => Color::Green,
-            "long_break" => Color::Blue,
-            _ => Color::White,
-        };
-        
-        print!("{:>12} ", stat.session_type);
-        println!("{} {}", bar.with(color), stat.total_minutes);
+        lines.push(vec![
+            (format!("{:>12} ", stat.session_type), Color::Reset),
+            (bar(stat.total_minutes, max_minutes, chart_width, theme), theme.session_type_color(&stat.session_type)),
+            (format!(" {}", stat.total_minutes), Color::Reset),
+        ]);
     }
-    
-    println!("{}", "-".repeat(chart_width + 10));
+
+    lines.push(plain("-".repeat(chart_width + 10)));
+    lines
+}
+
+/// Display a bar chart of interruptions by hour of the day
+pub fn display_interruption_chart(stats: &[InterruptionsHourly], theme: &Theme) -> io::Result<()> {
+    print_lines(&interruption_chart_lines(stats, terminal_width(), theme));
     Ok(())
 }
 
-/// Create a horizontal bar chart from a set of data points
-pub fn draw_horizontal_bar_chart<T: AsRef<str>>(
+/// Interruptions by hour of the day, internal and external stacked in one bar,
+/// laid out for a region `width` columns wide
+pub fn interruption_chart_lines(stats: &[InterruptionsHourly], width: u16, theme: &Theme) -> Vec<StyledLine> {
+    let mut lines = Vec::new();
+    if stats.is_empty() {
+        return lines;
+    }
+
+    let chart_width = chart_width(width);
+    let max_total = stats.iter()
+        .map(|s| s.internal + s.external)
+        .max()
+        .unwrap_or(1);
+
+    lines.push(plain(""));
+    lines.push(vec![
+        ("Interruptions by Hour (".to_string(), Color::Reset),
+        ("internal".to_string(), theme.chart[0]),
+        (", ".to_string(), Color::Reset),
+        ("external".to_string(), theme.chart[1]),
+        ("):".to_string(), Color::Reset),
+    ]);
+    lines.push(plain("-".repeat(chart_width + 10)));
+
+    for stat in stats {
+        let total = stat.internal + stat.external;
+        // Size the whole bar first so rounding never makes it longer than the total
+        let total_bar = bar(total, max_total, chart_width, theme);
+        let internal_bar = bar(stat.internal, max_total, chart_width, theme);
+        let external_len = total_bar.chars().count().saturating_sub(internal_bar.chars().count());
+        lines.push(vec![
+            (format!("{:>12} ", format!("{:02}:00", stat.hour)), Color::Reset),
+            (internal_bar, theme.chart[0]),
+            (theme.symbols.block.repeat(external_len), theme.chart[1]),
+            (format!(" {}", total), Color::Reset),
+        ]);
+    }
+
+    lines.push(plain("-".repeat(chart_width + 10)));
+    lines
+}
+
+/// Create a horizontal bar chart from a set of data points, laid out for a region `width` columns wide
+pub fn horizontal_bar_chart_lines<T: AsRef<str>>(
     labels: &[T],
     values: &[i64],
     max_value: i64,
     title: &str,
     color: Color,
-) -> io::Result<()> {
+    width: u16,
+    theme: &Theme,
+) -> Vec<StyledLine> {
+    let mut lines = Vec::new();
     if labels.is_empty() || values.is_empty() || labels.len() != values.len() {
-        return Ok(());
+        return lines;
     }
-    
-    // Get terminal width
-    let (width, _) = terminal::size()?;
-    let chart_width = std::cmp::min(MAX_CHART_WIDTH, width as usize - 20);
-    
-    println!("\n{}:", title);
-    println!("{}", "-".repeat(chart_width + 10));
-    
-    for (_, (label, &value)) in labels.iter().zip(values.iter()).enumerate() {
-        let bar_length = ((value as f64 / max_value as f64) * chart_width as f64) as usize;
-        let bar = "█".repeat(bar_length);
-        
-        print!("{:>12} ", label.as_ref());
-        println!("{} {}", bar.with(color), value);
+
+    let chart_width = chart_width(width);
+
+    lines.push(plain(""));
+    lines.push(plain(format!("{}:", title)));
+    lines.push(plain("-".repeat(chart_width + 10)));
+
+    for (label, &value) in labels.iter().zip(values.iter()) {
+        lines.push(vec![
+            (format!("{:>12} ", label.as_ref()), Color::Reset),
+            (bar(value, max_value, chart_width, theme), color),
+            (format!(" {}", value), Color::Reset),
+        ]);
     }
-    
-    println!("{}", "-".repeat(chart_width + 10));
-    Ok(())
+
+    lines.push(plain("-".repeat(chart_width + 10)));
+    lines
 }
@@ -0,0 +1,339 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crossterm::style::Color;
+use thiserror::Error;
+
+use crate::keymap::config_dir;
+use crate::pomodoro::PomodoroState;
+
+/// Characters used to draw bars, dots, separators, rules and boxes
+#[derive(Debug, Clone, Copy)]
+pub struct Symbols {
+    pub block: &'static str,
+    pub shade: &'static str,
+    pub dot_done: &'static str,
+    pub dot_todo: &'static str,
+    pub rule: &'static str,
+    pub separator: &'static str,
+    pub arrow: &'static str,
+    pub box_corners: [&'static str; 4],
+    pub box_side: &'static str,
+}
+
+impl Symbols {
+    pub const UNICODE: Symbols = Symbols {
+        block: "█",
+        shade: "░",
+        dot_done: "●",
+        dot_todo: "○",
+        rule: "─",
+        separator: "·",
+        arrow: "›",
+        box_corners: ["┌", "┐", "└", "┘"],
+        box_side: "│",
+    };
+
+    /// Plain ASCII for terminals, fonts and screen readers that mangle anything else
+    pub const ASCII: Symbols = Symbols {
+        block: "#",
+        shade: "-",
+        dot_done: "*",
+        dot_todo: "o",
+        rule: "-",
+        separator: "|",
+        arrow: ">",
+        box_corners: ["+", "+", "+", "+"],
+        box_side: "|",
+    };
+}
+
+/// Colors and symbols for everything the program draws
+#[derive(Debug, Clone)]
+pub struct Theme {
+    pub idle: Color,
+    pub work: Color,
+    pub short_break: Color,
+    pub long_break: Color,
+    pub paused: Color,
+    /// Regular text
+    pub text: Color,
+    /// Hints, footers and other secondary text
+    pub muted: Color,
+    /// Selected tab
+    pub accent: Color,
+    pub success: Color,
+    pub failure: Color,
+    pub warning: Color,
+    /// Bar colors for the daily charts, in order
+    pub chart: [Color; 3],
+    pub symbols: Symbols,
+}
+
+#[derive(Error, Debug)]
+pub enum ThemeError {
+    #[error("Unknown theme '{0}'. Built-in themes are: dark, light, high-contrast, monochrome")]
+    Unknown(String),
+
+    #[error("Failed to read {}: {source}", path.display())]
+    Io { path: PathBuf, source: io::Error },
+
+    #[error("{}:{line}: {message}", path.display())]
+    Syntax { path: PathBuf, line: usize, message: String },
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    /// For terminals with a dark background; the original look
+    pub fn dark() -> Self {
+        Self {
+            idle: Color::White,
+            work: Color::Red,
+            short_break: Color::Green,
+            long_break: Color::Blue,
+            paused: Color::Yellow,
+            text: Color::White,
+            muted: Color::DarkGrey,
+            accent: Color::Cyan,
+            success: Color::Green,
+            failure: Color::Red,
+            warning: Color::Yellow,
+            chart: [Color::Cyan, Color::Yellow, Color::Green],
+            symbols: Symbols::UNICODE,
+        }
+    }
+
+    /// For terminals with a light background
+    pub fn light() -> Self {
+        Self {
+            idle: Color::Black,
+            work: Color::DarkRed,
+            short_break: Color::DarkGreen,
+            long_break: Color::DarkBlue,
+            paused: Color::DarkYellow,
+            text: Color::Black,
+            muted: Color::DarkGrey,
+            accent: Color::DarkCyan,
+            success: Color::DarkGreen,
+            failure: Color::DarkRed,
+            warning: Color::DarkYellow,
+            chart: [Color::DarkCyan, Color::DarkMagenta, Color::DarkGreen],
+            symbols: Symbols::UNICODE,
+        }
+    }
+
+    /// Bright colors only, and no dimmed text
+    pub fn high_contrast() -> Self {
+        Self {
+            idle: Color::White,
+            work: Color::Red,
+            short_break: Color::Green,
+            long_break: Color::Cyan,
+            paused: Color::Yellow,
+            text: Color::White,
+            muted: Color::White,
+            accent: Color::Yellow,
+            success: Color::Green,
+            failure: Color::Red,
+            warning: Color::Yellow,
+            chart: [Color::Cyan, Color::Yellow, Color::Green],
+            symbols: Symbols::UNICODE,
+        }
+    }
+
+    /// The terminal's default color for everything
+    pub fn monochrome() -> Self {
+        Self {
+            idle: Color::Reset,
+            work: Color::Reset,
+            short_break: Color::Reset,
+            long_break: Color::Reset,
+            paused: Color::Reset,
+            text: Color::Reset,
+            muted: Color::Reset,
+            accent: Color::Reset,
+            success: Color::Reset,
+            failure: Color::Reset,
+            warning: Color::Reset,
+            chart: [Color::Reset; 3],
+            symbols: Symbols::UNICODE,
+        }
+    }
+
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "high-contrast" => Some(Self::high_contrast()),
+            "monochrome" => Some(Self::monochrome()),
+            _ => None,
+        }
+    }
+
+    pub fn state_color(&self, state: PomodoroState) -> Color {
+        match state {
+            PomodoroState::Idle => self.idle,
+            PomodoroState::Work => self.work,
+            PomodoroState::ShortBreak => self.short_break,
+            PomodoroState::LongBreak => self.long_break,
+            PomodoroState::Paused => self.paused,
+        }
+    }
+
+    /// Color for a session type as stored in the database. Any other type is a work
+    /// interval named in a custom sequence.
+    pub fn session_type_color(&self, session_type: &str) -> Color {
+        self.state_color(PomodoroState::from_name(session_type).unwrap_or(PomodoroState::Work))
+    }
+
+    /// Resolve the theme to use. `name` is a built-in theme, a theme file, or the name
+    /// of a file in `~/.config/pomodoro-cli/themes/<name>.conf`; without it `theme.conf` in the
+    /// config directory is used if it exists. `NO_COLOR` turns every color off
+    /// and `ascii` replaces the Unicode symbols.
+    pub fn load(name: Option<&str>, ascii: bool) -> Result<Self, ThemeError> {
+        let mut theme = match name {
+            Some(name) => match Self::builtin(name) {
+                Some(theme) => theme,
+                None if Path::new(name).exists() => Self::from_file(Path::new(name))?,
+                None => {
+                    let named = config_dir()
+                        .map(|dir| dir.join("themes").join(format!("{}.conf", name)))
+                        .filter(|path| path.exists())
+                        .ok_or_else(|| ThemeError::Unknown(name.to_string()))?;
+                    Self::from_file(&named)?
+                }
+            },
+            None => match config_dir().map(|dir| dir.join("theme.conf")).filter(|path| path.exists()) {
+                Some(path) => Self::from_file(&path)?,
+                None => Self::default(),
+            },
+        };
+
+        // https://no-color.org: any non-empty value disables color
+        if std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            theme = Self { symbols: theme.symbols, ..Self::monochrome() };
+        }
+        if ascii {
+            theme.symbols = Symbols::ASCII;
+        }
+
+        Ok(theme)
+    }
+
+    /// Read a theme file. Each line is `key = value`; `base = <built-in theme>` picks
+    /// the starting point (dark by default) and the other keys override its colors.
+    fn from_file(path: &Path) -> Result<Self, ThemeError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|source| ThemeError::Io { path: path.to_path_buf(), source })?;
+
+        let mut entries = Vec::new();
+        for (index, line) in contents.lines().enumerate() {
+            // Only whole lines are comments, since `#` also starts hex colors
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.split_once('=') {
+                Some((key, value)) => entries.push((index + 1, key.trim(), value.trim())),
+                None => {
+                    return Err(ThemeError::Syntax {
+                        path: path.to_path_buf(),
+                        line: index + 1,
+                        message: "expected `key = value`".to_string(),
+                    })
+                }
+            }
+        }
+
+        // The base applies first wherever it appears in the file
+        let mut theme = Self::default();
+        for (line, key, value) in &entries {
+            if *key == "base" {
+                theme = Self::builtin(value).ok_or_else(|| ThemeError::Syntax {
+                    path: path.to_path_buf(),
+                    line: *line,
+                    message: format!("unknown base theme `{}`", value),
+                })?;
+            }
+        }
+
+        for (line, key, value) in entries {
+            let syntax_error = |message: String| ThemeError::Syntax { path: path.to_path_buf(), line, message };
+
+            if key == "base" {
+                continue;
+            }
+            if key == "ascii" {
+                theme.symbols = match value {
+                    "true" => Symbols::ASCII,
+                    "false" => Symbols::UNICODE,
+                    _ => return Err(syntax_error(format!("expected true or false, found `{}`", value))),
+                };
+                continue;
+            }
+
+            let color = parse_color(value).ok_or_else(|| syntax_error(format!("unknown color `{}`", value)))?;
+            let slot = match key {
+                "idle" => &mut theme.idle,
+                "work" => &mut theme.work,
+                "short_break" => &mut theme.short_break,
+                "long_break" => &mut theme.long_break,
+                "paused" => &mut theme.paused,
+                "text" => &mut theme.text,
+                "muted" => &mut theme.muted,
+                "accent" => &mut theme.accent,
+                "success" => &mut theme.success,
+                "failure" => &mut theme.failure,
+                "warning" => &mut theme.warning,
+                "chart1" => &mut theme.chart[0],
+                "chart2" => &mut theme.chart[1],
+                "chart3" => &mut theme.chart[2],
+                _ => return Err(syntax_error(format!("unknown key `{}`", key))),
+            };
+            *slot = color;
+        }
+
+        Ok(theme)
+    }
+}
+
+/// Parse a color name (`red`, `dark_grey`, `default`), `#rrggbb` or an ANSI color number
+fn parse_color(value: &str) -> Option<Color> {
+    if let Some(hex) = value.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        return Some(Color::Rgb { r: channel(0)?, g: channel(2)?, b: channel(4)? });
+    }
+    if let Ok(number) = value.parse::<u8>() {
+        return Some(Color::AnsiValue(number));
+    }
+
+    let color = match value.to_ascii_lowercase().replace('-', "_").as_str() {
+        "default" | "reset" | "none" => Color::Reset,
+        "black" => Color::Black,
+        "dark_grey" | "dark_gray" => Color::DarkGrey,
+        "red" => Color::Red,
+        "dark_red" => Color::DarkRed,
+        "green" => Color::Green,
+        "dark_green" => Color::DarkGreen,
+        "yellow" => Color::Yellow,
+        "dark_yellow" => Color::DarkYellow,
+        "blue" => Color::Blue,
+        "dark_blue" => Color::DarkBlue,
+        "magenta" => Color::Magenta,
+        "dark_magenta" => Color::DarkMagenta,
+        "cyan" => Color::Cyan,
+        "dark_cyan" => Color::DarkCyan,
+        "white" => Color::White,
+        "grey" | "gray" => Color::Grey,
+        _ => return None,
+    };
+    Some(color)
+}
@@ -0,0 +1,1009 @@
+use std::io::{self, Write};
+use std::ops::Range;
+
+use crossterm::{
+    cursor, queue,
+    style::{Attribute, Color, Print, SetAttribute, SetForegroundColor},
+    terminal::{Clear, ClearType},
+};
+
+use chrono::Local;
+
+use crate::db::{Database, ExtensionSummary, Note, PomodoroSession, SessionOutcome, SessionTypeSummary, StatsDaily, StatsSummary};
+use crate::keymap::{Action, Keymap};
+use crate::pomodoro::{InterruptionKind, PomodoroState, PomodoroStatus};
+use crate::stats_chart::{self, StyledLine};
+use crate::theme::Theme;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Cell {
+    ch: char,
+    fg: Color,
+    bold: bool,
+}
+
+const BLANK: Cell = Cell { ch: ' ', fg: Color::Reset, bold: false };
+
+/// One screenful of styled characters. Text is laid out one cell per `char`,
+/// so only single-width characters should be drawn.
+pub struct Frame {
+    width: u16,
+    height: u16,
+    cells: Vec<Cell>,
+}
+
+impl Frame {
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![BLANK; width as usize * height as usize],
+        }
+    }
+
+    /// Draw `text` starting at column `x`, clipped to the frame
+    pub fn print(&mut self, x: u16, y: u16, text: &str, fg: Color, bold: bool) {
+        if y >= self.height {
+            return;
+        }
+        let row = y as usize * self.width as usize;
+        for (i, ch) in text.chars().enumerate() {
+            let column = x as usize + i;
+            if column >= self.width as usize {
+                break;
+            }
+            self.cells[row + column] = Cell { ch, fg, bold };
+        }
+    }
+
+    /// Draw a line of differently colored parts starting at column `x`
+    pub fn print_styled(&mut self, x: u16, y: u16, line: &StyledLine) {
+        let mut x = x;
+        for (text, fg) in line {
+            self.print(x, y, text, *fg, false);
+            x = x.saturating_add(text.chars().count() as u16);
+        }
+    }
+
+    /// Draw `text` horizontally centered on row `y`
+    pub fn print_centered(&mut self, y: u16, text: &str, fg: Color, bold: bool) {
+        self.print_centered_in(0, self.width, y, text, fg, bold);
+    }
+
+    /// Draw `text` centered within the `width` columns starting at `x`
+    pub fn print_centered_in(&mut self, x: u16, width: u16, y: u16, text: &str, fg: Color, bold: bool) {
+        let len = text.chars().count() as u16;
+        self.print(x + width.saturating_sub(len) / 2, y, text, fg, bold);
+    }
+}
+
+/// Writes frames to the terminal, sending only the cells that changed since the last one
+#[derive(Default)]
+pub struct Renderer {
+    previous: Option<Frame>,
+}
+
+impl Renderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Repaint everything on the next frame, e.g. after something else wrote to the terminal
+    pub fn invalidate(&mut self) {
+        self.previous = None;
+    }
+
+    pub fn render(&mut self, out: &mut impl Write, frame: Frame) -> io::Result<()> {
+        // A resize (or the first frame) invalidates whatever is on screen
+        let previous = self
+            .previous
+            .take()
+            .filter(|p| p.width == frame.width && p.height == frame.height);
+        if previous.is_none() {
+            queue!(out, SetAttribute(Attribute::Reset), Clear(ClearType::All))?;
+        }
+
+        let mut style: Option<(Color, bool)> = None;
+        for y in 0..frame.height {
+            // Whether the cursor already sits where the next changed cell goes
+            let mut in_run = false;
+            for x in 0..frame.width {
+                let index = y as usize * frame.width as usize + x as usize;
+                let cell = frame.cells[index];
+                if previous.as_ref().is_some_and(|p| p.cells[index] == cell) {
+                    in_run = false;
+                    continue;
+                }
+
+                if !in_run {
+                    queue!(out, cursor::MoveTo(x, y))?;
+                    in_run = true;
+                }
+                if style.is_none_or(|(_, bold)| bold != cell.bold) {
+                    let attribute = if cell.bold { Attribute::Bold } else { Attribute::NormalIntensity };
+                    queue!(out, SetAttribute(attribute))?;
+                }
+                if style.is_none_or(|(fg, _)| fg != cell.fg) {
+                    queue!(out, SetForegroundColor(cell.fg))?;
+                }
+                style = Some((cell.fg, cell.bold));
+                queue!(out, Print(cell.ch))?;
+            }
+        }
+
+        queue!(out, SetAttribute(Attribute::Reset))?;
+        out.flush()?;
+        self.previous = Some(frame);
+        Ok(())
+    }
+}
+
+// 3x5 bitmaps for the countdown, drawn two columns per pixel
+const DIGIT_GLYPHS: [[&str; 5]; 10] = [
+    ["###", "# #", "# #", "# #", "###"],
+    [" # ", "## ", " # ", " # ", "###"],
+    ["###", "  #", "###", "#  ", "###"],
+    ["###", "  #", "###", "  #", "###"],
+    ["# #", "# #", "###", "  #", "  #"],
+    ["###", "#  ", "###", "  #", "###"],
+    ["###", "#  ", "###", "# #", "###"],
+    ["###", "  #", "  #", "  #", "  #"],
+    ["###", "# #", "###", "# #", "###"],
+    ["###", "# #", "###", "  #", "###"],
+];
+const COLON_GLYPH: [&str; 5] = [" ", "#", " ", "#", " "];
+const PLUS_GLYPH: [&str; 5] = ["   ", " # ", "###", " # ", "   "];
+const BIG_TEXT_HEIGHT: u16 = 5;
+
+/// Render digits, colons and plus signs as large block characters, one string per row
+fn big_text(text: &str, block: &str) -> Vec<String> {
+    let mut rows = vec![String::new(); BIG_TEXT_HEIGHT as usize];
+    for (i, ch) in text.chars().enumerate() {
+        let glyph = match ch {
+            '0'..='9' => &DIGIT_GLYPHS[ch as usize - '0' as usize],
+            ':' => &COLON_GLYPH,
+            '+' => &PLUS_GLYPH,
+            _ => continue,
+        };
+        for (row, pixels) in rows.iter_mut().zip(glyph) {
+            if i > 0 {
+                row.push(' ');
+            }
+            for pixel in pixels.chars() {
+                if pixel == '#' {
+                    row.push_str(block);
+                    row.push_str(block);
+                } else {
+                    row.push_str("  ");
+                }
+            }
+        }
+    }
+    rows
+}
+
+fn progress_bar(status: &PomodoroStatus, width: usize, theme: &Theme) -> String {
+    // A flowtime session has no end to measure against, so show the break it has earned
+    if let Some(earned) = status.earned_break_seconds() {
+        return format!("Break earned: {:02}:{:02}", earned / 60, earned % 60);
+    }
+    let fraction = if status.duration_seconds > 0 {
+        let elapsed = status.duration_seconds - status.remaining_seconds;
+        (elapsed as f64 / status.duration_seconds as f64).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let filled = (fraction * width as f64).round() as usize;
+    format!(
+        "{}{} {:>3}%",
+        theme.symbols.block.repeat(filled),
+        theme.symbols.shade.repeat(width - filled),
+        (fraction * 100.0).round() as i64
+    )
+}
+
+/// First of `variants` (longest first) that fits in `width`, or the last one
+fn fit(width: u16, variants: Vec<String>) -> String {
+    let last = variants.len().saturating_sub(1);
+    variants
+        .into_iter()
+        .enumerate()
+        .find(|(i, text)| *i == last || text.chars().count() <= width as usize)
+        .map(|(_, text)| text)
+        .unwrap_or_default()
+}
+
+/// One dot per work interval in the current long-break cycle
+fn cycle_indicator(status: &PomodoroStatus, width: u16, theme: &Theme) -> String {
+    // A custom sequence shows each of its steps instead of the pomodoros in a cycle
+    if let Some(sequence) = &status.sequence {
+        let symbols = theme.symbols;
+        let dots: Vec<&str> = (1..=sequence.steps)
+            .map(|step| if step < sequence.step { symbols.dot_done } else { symbols.dot_todo })
+            .collect();
+        return fit(width, vec![
+            format!("{}  step {}/{}", dots.join(" "), sequence.step, sequence.steps),
+            format!("step {}/{}", sequence.step, sequence.steps),
+        ]);
+    }
+    // Flowtime has no long breaks, so there's no cycle to show
+    if status.break_fraction.is_some() {
+        let done = status.completed_pomodoros;
+        let sessions = if done == 1 { "session" } else { "sessions" };
+        return fit(width, vec![format!("{} {} completed", done, sessions), format!("{} done", done)]);
+    }
+    let cycle = status.long_break_after.max(1);
+    let mut done = status.completed_pomodoros % cycle;
+
+    // On the long break itself the finished cycle is still shown as complete
+    let on_long_break = status.state == PomodoroState::LongBreak
+        || status.prev_state == Some(PomodoroState::LongBreak);
+    if done == 0 && status.completed_pomodoros > 0 && on_long_break {
+        done = cycle;
+    }
+
+    let symbols = theme.symbols;
+    let dots: Vec<&str> = (0..cycle).map(|i| if i < done { symbols.dot_done } else { symbols.dot_todo }).collect();
+    fit(width, vec![
+        format!("{}  {}/{} until long break", dots.join(" "), done, cycle),
+        format!("{}  {}/{}", dots.join(" "), done, cycle),
+        format!("{}/{} until long break", done, cycle),
+    ])
+}
+
+// How many recent sessions are scanned for today's list
+const TODAY_SESSION_LIMIT: i64 = 100;
+const DAILY_CHART_DAYS: i64 = 7;
+const MAX_BAR_WIDTH: u16 = 40;
+// Below this width or height the view collapses to a single line
+const MIN_WIDTH: u16 = 30;
+const MIN_HEIGHT: u16 = 3;
+
+/// A line of the timer view, centered when drawn
+struct Line {
+    text: String,
+    fg: Color,
+    bold: bool,
+}
+
+impl Line {
+    fn new(text: impl Into<String>, fg: Color, bold: bool) -> Self {
+        Self { text: text.into(), fg, bold }
+    }
+
+    fn blank() -> Self {
+        Self::new("", Color::Reset, false)
+    }
+}
+
+fn state_text(status: &PomodoroStatus) -> String {
+    match (status.state, status.prev_state) {
+        (PomodoroState::Paused, _) if status.is_overtime() => "Paused (Overtime)".to_string(),
+        (_, _) if status.is_overtime() => "Overtime".to_string(),
+        (PomodoroState::Paused, Some(next)) => match status.auto_start_in {
+            Some(seconds) => format!("{} starts in {}s", short_label(status, next), seconds),
+            None if status.is_open_ended() => "Paused (Flowtime)".to_string(),
+            None => format!("Paused ({})", status.interval_label()),
+        },
+        (_, _) if status.is_open_ended() => "Flowtime".to_string(),
+        (_, _) => status.interval_label(),
+    }
+}
+
+/// Name of an interval that fits before "starts"
+fn short_label(status: &PomodoroStatus, state: PomodoroState) -> String {
+    match (&status.sequence, state) {
+        (Some(sequence), _) if sequence.name != state.as_str() => sequence.label.clone(),
+        (_, PomodoroState::Work) => "Work".to_string(),
+        _ => state.label().to_string(),
+    }
+}
+
+/// Countdown, or in overtime the time past zero counting up, or in flowtime the time worked
+fn time_text(status: &PomodoroStatus) -> String {
+    if status.is_overtime() {
+        let overtime = -status.remaining_seconds;
+        return format!("+{:02}:{:02}", overtime / 60, overtime % 60);
+    }
+    if status.is_open_ended() {
+        let worked = -status.remaining_seconds;
+        return format!("{:02}:{:02}", worked / 60, worked % 60);
+    }
+    format!("{:02}:{:02}", status.remaining_seconds / 60, status.remaining_seconds % 60)
+}
+
+/// Terminal window title, with the time first so it survives truncated tab and tmux window names
+pub fn title_text(status: &PomodoroStatus) -> String {
+    match status.state {
+        PomodoroState::Idle => "Idle - pomodoro".to_string(),
+        _ => format!("{} {} - pomodoro", time_text(status), state_text(status)),
+    }
+}
+
+fn task_line(status: &PomodoroStatus, theme: &Theme) -> Line {
+    match (&status.project, &status.task) {
+        (Some(project), Some(task)) => {
+            Line::new(format!("Task: {} {} {}", project, theme.symbols.arrow, task), theme.text, false)
+        }
+        (Some(project), None) => Line::new(format!("Project: {}", project), theme.text, false),
+        (None, Some(task)) => Line::new(format!("Task: {}", task), theme.text, false),
+        (None, None) => Line::new("No task", theme.muted, false),
+    }
+}
+
+fn today_line(today: &StatsDaily, width: u16, theme: &Theme) -> Line {
+    let rate = (today.completion_rate * 100.0).round() as i64;
+    let sep = theme.symbols.separator;
+    let text = fit(width, vec![
+        format!(
+            "Today: {}/{} sessions {sep} {} min {sep} {}% completed",
+            today.completed_work_sessions, today.work_sessions, today.total_work_minutes, rate
+        ),
+        format!(
+            "Today: {}/{} {sep} {} min {sep} {}%",
+            today.completed_work_sessions, today.work_sessions, today.total_work_minutes, rate
+        ),
+    ]);
+    Line::new(text, theme.muted, false)
+}
+
+/// Lay out the timer view for a terminal of the given size. Shrinks from the full
+/// view with big digits, to a plain list of lines, to a single line for tiny panes.
+fn timer_frame(
+    width: u16,
+    height: u16,
+    status: &PomodoroStatus,
+    today: Option<&StatsDaily>,
+    footers: &[Footer],
+    theme: &Theme,
+    hits: &mut Vec<Hit>,
+) -> Frame {
+    let mut frame = Frame::new(width, height);
+    if width < MIN_WIDTH || height < MIN_HEIGHT {
+        draw_compact(&mut frame, status, theme, hits);
+        return frame;
+    }
+
+    let color = theme.state_color(status.state);
+    // Leave room for the percentage after the bar
+    let bar = progress_bar(status, width.saturating_sub(10).min(MAX_BAR_WIDTH) as usize, theme);
+    let big_time = big_text(&time_text(status), theme.symbols.block);
+    let big_width = big_time[0].chars().count() as u16;
+
+    // The last row is kept for the footer
+    let available = (height - 1) as usize;
+
+    let mut full = vec![
+        Line::new("POMODORO", theme.text, true),
+        Line::blank(),
+        Line::new(state_text(status), color, true),
+        Line::blank(),
+    ];
+    full.extend(big_time.into_iter().map(|row| Line::new(row, color, true)));
+    full.extend([
+        Line::blank(),
+        Line::new(bar.clone(), color, false),
+        Line::blank(),
+        Line::new(cycle_indicator(status, width, theme), theme.text, false),
+        task_line(status, theme),
+    ]);
+    if let Some(today) = today {
+        full.extend([Line::blank(), today_line(today, width, theme)]);
+    }
+
+    let lines = if big_width + 4 <= width && full.len() <= available {
+        full
+    } else {
+        // Most important first; whatever doesn't fit is dropped from the end
+        let mut lines = vec![
+            Line::new(format!("{}  {}", state_text(status), time_text(status)), color, true),
+            Line::new(bar, color, false),
+            task_line(status, theme),
+            Line::new(cycle_indicator(status, width, theme), theme.text, false),
+        ];
+        if let Some(today) = today {
+            lines.push(today_line(today, width, theme));
+        }
+        lines.truncate(available);
+        lines
+    };
+
+    // Center the content vertically above the footer
+    let top = (available - lines.len()) / 2;
+    for (i, line) in lines.iter().enumerate() {
+        frame.print_centered((top + i) as u16, &line.text, line.fg, line.bold);
+    }
+
+    draw_footer(&mut frame, height - 1, footers, theme.muted, hits);
+
+    frame
+}
+
+/// Everything on one line, clipped to the width, for panes only a few cells big.
+/// There is no room for buttons, so a click anywhere pauses or resumes.
+fn draw_compact(frame: &mut Frame, status: &PomodoroStatus, theme: &Theme, hits: &mut Vec<Hit>) {
+    hits.push(Hit {
+        rows: 0..frame.height,
+        columns: 0..frame.width,
+        target: Target::Action(Action::Toggle),
+    });
+    let mut text = format!("{} {}", state_text(status), time_text(status));
+    if let Some(task) = &status.task {
+        text.push_str(&format!(" {} ", theme.symbols.separator));
+        text.push_str(task);
+    }
+    frame.print(0, frame.height / 2, &text, theme.state_color(status.state), true);
+}
+
+/// Pages of the interactive view
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tab {
+    #[default]
+    Timer,
+    Today,
+    Daily,
+    Summary,
+    Types,
+}
+
+impl Tab {
+    pub const ALL: [Tab; 5] = [Tab::Timer, Tab::Today, Tab::Daily, Tab::Summary, Tab::Types];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Tab::Timer => "Timer",
+            Tab::Today => "Today",
+            Tab::Daily => "Daily",
+            Tab::Summary => "Summary",
+            Tab::Types => "Types",
+        }
+    }
+
+    fn index(&self) -> usize {
+        Tab::ALL.iter().position(|tab| tab == self).unwrap_or(0)
+    }
+
+    pub fn next(&self) -> Tab {
+        Tab::ALL[(self.index() + 1) % Tab::ALL.len()]
+    }
+
+    pub fn previous(&self) -> Tab {
+        Tab::ALL[(self.index() + Tab::ALL.len() - 1) % Tab::ALL.len()]
+    }
+
+    /// Tab selected by a number key, counting from 1
+    pub fn from_digit(digit: char) -> Option<Tab> {
+        let index = digit.to_digit(10)? as usize;
+        Tab::ALL.get(index.checked_sub(1)?).copied()
+    }
+}
+
+/// Database figures shown by the view, refreshed when the timer changes state
+#[derive(Default)]
+pub struct StatsData {
+    pub today: Option<StatsDaily>,
+    today_sessions: Vec<PomodoroSession>,
+    daily: Vec<StatsDaily>,
+    summary: Option<StatsSummary>,
+    types: Vec<SessionTypeSummary>,
+    extensions: Vec<ExtensionSummary>,
+}
+
+impl StatsData {
+    /// Run every query the view needs. A failing query only leaves its section empty,
+    /// so a busy or damaged database never takes the timer down.
+    pub fn load(database: &Database) -> Self {
+        let today = Local::now().date_naive();
+        let today_sessions = database
+            .get_session_stats(TODAY_SESSION_LIMIT)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|session| session.start_time.date_naive() == today)
+            .collect();
+
+        Self {
+            today: database.get_daily_stats(1).ok().and_then(|stats| stats.into_iter().next()),
+            today_sessions,
+            daily: database.get_daily_stats(DAILY_CHART_DAYS).unwrap_or_default(),
+            summary: database.get_summary_stats().ok(),
+            types: database.get_session_type_stats().unwrap_or_default(),
+            extensions: database.get_extension_stats().unwrap_or_default(),
+        }
+    }
+}
+
+fn today_lines(stats: &StatsData, width: u16, theme: &Theme) -> Vec<StyledLine> {
+    let mut lines = vec![vec![("Today's Sessions:".to_string(), Color::Reset)]];
+    if stats.today_sessions.is_empty() {
+        lines.push(vec![("No sessions recorded today.".to_string(), theme.muted)]);
+        return lines;
+    }
+
+    for session in &stats.today_sessions {
+        let (outcome, color) = match session.outcome {
+            Some(SessionOutcome::Completed) => ("Completed", theme.success),
+            Some(outcome) => (outcome.label(), theme.failure),
+            None => ("In progress", theme.warning),
+        };
+        let mut line = vec![
+            (
+                format!(
+                    "{}  {:<11} {:>3} min  ",
+                    session.start_time.format("%H:%M"),
+                    session.session_type,
+                    session.duration_seconds / 60
+                ),
+                Color::Reset,
+            ),
+            (outcome.to_string(), color),
+        ];
+        if session.overtime_seconds >= 60 {
+            line.push((format!("  +{} min overtime", session.overtime_seconds / 60), theme.muted));
+        }
+        lines.push(line);
+    }
+
+    lines.extend(stats_chart::session_chart_lines(&stats.today_sessions, width, theme));
+    lines
+}
+
+/// Summary line for the extensions of one interval type, aligned with the other summary lines
+pub fn extension_summary_text(extension: &ExtensionSummary) -> String {
+    let extended = match extension.session_type.as_str() {
+        "work" => "work sessions",
+        "short_break" => "short breaks",
+        "long_break" => "long breaks",
+        other => other,
+    };
+    format!("{:<25}{} ({} min)", format!("Extended {}:", extended), extension.count, extension.total_minutes)
+}
+
+fn summary_lines(stats: &StatsData, theme: &Theme) -> Vec<StyledLine> {
+    let Some(summary) = &stats.summary else {
+        return vec![vec![("Summary unavailable.".to_string(), theme.muted)]];
+    };
+
+    [
+        "Pomodoro Summary Statistics:".to_string(),
+        String::new(),
+        format!("Total work sessions:     {}", summary.total_work_sessions),
+        format!("Total focus minutes:     {}", summary.total_work_minutes),
+        format!("Overtime minutes:        {}", summary.overtime_minutes),
+        format!("Completed sessions:      {}", summary.completed_sessions),
+        format!("Completion rate:         {:.1}%", summary.completion_rate * 100.0),
+        format!("Avg sessions per day:    {:.1}", summary.avg_sessions_per_day),
+        format!("Longest streak:          {} days", summary.longest_streak_days),
+        format!("Current streak:          {} days", summary.current_streak_days),
+    ]
+    .into_iter()
+    .chain(stats.extensions.iter().map(extension_summary_text))
+    .map(|text| vec![(text, Color::Reset)])
+    .collect()
+}
+
+fn no_data(lines: Vec<StyledLine>, theme: &Theme) -> Vec<StyledLine> {
+    if lines.is_empty() {
+        vec![vec![("No data for the selected period.".to_string(), theme.muted)]]
+    } else {
+        lines
+    }
+}
+
+/// Notes captured during work, put up for review when a break comes round
+pub struct Triage {
+    notes: Vec<Note>,
+    selected: usize,
+}
+
+impl Triage {
+    pub fn move_by(&mut self, delta: isize) {
+        self.selected = self.selected.saturating_add_signed(delta).min(self.notes.len().saturating_sub(1));
+    }
+
+    /// Take the selected note off the list
+    pub fn take_selected(&mut self) -> Option<Note> {
+        if self.selected >= self.notes.len() {
+            return None;
+        }
+        let note = self.notes.remove(self.selected);
+        self.selected = self.selected.min(self.notes.len().saturating_sub(1));
+        Some(note)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.notes.is_empty()
+    }
+}
+
+/// Text being typed into the prompt line
+pub struct Prompt {
+    pub kind: PromptKind,
+    pub input: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PromptKind {
+    Task,
+    Note,
+    /// Optional reason for an interruption, logged when the prompt is submitted
+    Interruption(InterruptionKind),
+    /// Confirmation to abandon the work session: strict mode's phrase, or "yes" otherwise
+    Abandon { strict_phrase: Option<String> },
+}
+
+impl PromptKind {
+    fn label(&self) -> String {
+        match self {
+            PromptKind::Task => "Task: ".to_string(),
+            PromptKind::Note => "Note: ".to_string(),
+            PromptKind::Interruption(InterruptionKind::Internal) => "Internal interruption, reason (optional): ".to_string(),
+            PromptKind::Interruption(InterruptionKind::External) => "External interruption, reason (optional): ".to_string(),
+            PromptKind::Abandon { strict_phrase: Some(phrase) } => format!("Type \"{}\" to abandon this work session: ", phrase),
+            PromptKind::Abandon { strict_phrase: None } => "Abandon this work session? Type yes: ".to_string(),
+        }
+    }
+}
+
+/// Footer listing the keys for a set of actions. Each hint is also a button.
+struct Footer {
+    hints: Vec<(String, Action)>,
+    separator: &'static str,
+}
+
+impl Footer {
+    /// Hints for `actions`, skipping unbound ones
+    fn new(keymap: &Keymap, actions: &[(Action, &str)], separator: &'static str) -> Self {
+        let hints = actions
+            .iter()
+            .filter_map(|(action, label)| Some((format!("{} {}", keymap.hint(*action)?, label), *action)))
+            .collect();
+        Self { hints, separator }
+    }
+
+    fn width(&self) -> usize {
+        let text: usize = self.hints.iter().map(|(hint, _)| hint.chars().count()).sum();
+        text + self.hints.len().saturating_sub(1) * self.separator.len()
+    }
+}
+
+/// Draw the first of `footers` (longest first) that fits, centered on row `y`,
+/// and make each hint clickable
+fn draw_footer(frame: &mut Frame, y: u16, footers: &[Footer], fg: Color, hits: &mut Vec<Hit>) {
+    let last = footers.len().saturating_sub(1);
+    let Some(footer) = footers
+        .iter()
+        .enumerate()
+        .find(|(i, footer)| *i == last || footer.width() <= frame.width as usize)
+        .map(|(_, footer)| footer)
+    else {
+        return;
+    };
+
+    let mut x = frame.width.saturating_sub(footer.width() as u16) / 2;
+    for (hint, action) in &footer.hints {
+        let len = hint.chars().count() as u16;
+        frame.print(x, y, hint, fg, false);
+        hits.push(Hit { rows: y..y + 1, columns: x..x.saturating_add(len), target: Target::Action(*action) });
+        // Separators are blank, so skipping over them is enough
+        x = x.saturating_add(len + footer.separator.len() as u16);
+    }
+}
+
+/// Something on screen that reacts to a click
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Action(Action),
+    Tab(Tab),
+}
+
+/// Area of the last frame covered by a clickable target
+struct Hit {
+    rows: Range<u16>,
+    columns: Range<u16>,
+    target: Target,
+}
+
+/// What the view shows besides the timer itself: the selected tab, scrolling,
+/// the help overlay, the prompt line and short messages
+pub struct View {
+    tab: Tab,
+    // Stats tab the stats key returns to
+    last_stats_tab: Tab,
+    scroll: usize,
+    footers: Vec<Footer>,
+    stats_footers: Vec<Footer>,
+    help: Vec<String>,
+    show_help: bool,
+    prompt: Option<Prompt>,
+    triage: Option<Triage>,
+    message: Option<String>,
+    theme: Theme,
+    // Clickable areas of the last frame
+    hits: Vec<Hit>,
+}
+
+impl View {
+    pub fn new(keymap: &Keymap, theme: Theme) -> Self {
+        let footers = vec![
+            Footer::new(keymap, &[
+                (Action::Start, "start/resume"),
+                (Action::Pause, "pause"),
+                (Action::Next, "next"),
+                (Action::Stats, "stats"),
+                (Action::Help, "help"),
+                (Action::Quit, "quit"),
+            ], "   "),
+            Footer::new(keymap, &[
+                (Action::Start, "start"),
+                (Action::Pause, "pause"),
+                (Action::Next, "next"),
+                (Action::Help, "help"),
+                (Action::Quit, "quit"),
+            ], "  "),
+            Footer::new(keymap, &[(Action::Help, "help"), (Action::Quit, "quit")], "  "),
+        ];
+        let stats_footers = vec![
+            Footer::new(keymap, &[
+                (Action::NextTab, "next tab"),
+                (Action::ScrollDown, "down"),
+                (Action::ScrollUp, "up"),
+                (Action::Stats, "timer"),
+                (Action::Help, "help"),
+                (Action::Quit, "quit"),
+            ], "   "),
+            Footer::new(keymap, &[(Action::NextTab, "tab"), (Action::Help, "help"), (Action::Quit, "quit")], "  "),
+            Footer::new(keymap, &[(Action::Help, "help")], ""),
+        ];
+
+        let mut help: Vec<String> = Action::ALL
+            .iter()
+            .map(|action| {
+                let keys: Vec<String> = keymap.keys(*action).iter().map(ToString::to_string).collect();
+                let keys = if keys.is_empty() { "-".to_string() } else { keys.join(", ") };
+                format!("{:<16} {}", keys, action.description())
+            })
+            .collect();
+        help.push(format!("{:<16} {}", "1-5", "Select tab"));
+        help.push(format!("{:<16} {}", "ctrl-c", "Quit"));
+
+        Self {
+            tab: Tab::Timer,
+            last_stats_tab: Tab::Today,
+            scroll: 0,
+            footers,
+            stats_footers,
+            help,
+            show_help: false,
+            prompt: None,
+            triage: None,
+            message: None,
+            theme,
+            hits: Vec::new(),
+        }
+    }
+
+    pub fn tab(&self) -> Tab {
+        self.tab
+    }
+
+    pub fn select(&mut self, tab: Tab) {
+        if tab != Tab::Timer {
+            self.last_stats_tab = tab;
+        }
+        self.tab = tab;
+        self.scroll = 0;
+    }
+
+    /// Jump between the timer and the last stats tab
+    pub fn toggle_stats(&mut self) {
+        let tab = if self.tab == Tab::Timer { self.last_stats_tab } else { Tab::Timer };
+        self.select(tab);
+    }
+
+    pub fn scroll_by(&mut self, delta: isize) {
+        self.scroll = self.scroll.saturating_add_signed(delta);
+    }
+
+    /// Scroll to the top, or to the bottom (clamped once the content height is known)
+    pub fn scroll_to(&mut self, top: bool) {
+        self.scroll = if top { 0 } else { usize::MAX };
+    }
+
+    pub fn help_visible(&self) -> bool {
+        self.show_help
+    }
+
+    pub fn set_help(&mut self, visible: bool) {
+        self.show_help = visible;
+    }
+
+    pub fn open_prompt(&mut self, kind: PromptKind, input: String) {
+        self.prompt = Some(Prompt { kind, input });
+    }
+
+    pub fn prompt_mut(&mut self) -> Option<&mut Prompt> {
+        self.prompt.as_mut()
+    }
+
+    pub fn close_prompt(&mut self) -> Option<Prompt> {
+        self.prompt.take()
+    }
+
+    /// Offer `notes` for triage, unless there are none
+    pub fn open_triage(&mut self, notes: Vec<Note>) {
+        if !notes.is_empty() {
+            self.triage = Some(Triage { notes, selected: 0 });
+        }
+    }
+
+    pub fn triage_mut(&mut self) -> Option<&mut Triage> {
+        self.triage.as_mut()
+    }
+
+    pub fn close_triage(&mut self) {
+        self.triage = None;
+    }
+
+    /// Show `message` in place of the footer until the next key press
+    pub fn set_message(&mut self, message: impl Into<String>) {
+        self.message = Some(message.into());
+    }
+
+    pub fn clear_message(&mut self) {
+        self.message = None;
+    }
+
+    /// What a click at this cell of the last frame would hit
+    pub fn target_at(&self, column: u16, row: u16) -> Option<Target> {
+        self.hits
+            .iter()
+            .find(|hit| hit.rows.contains(&row) && hit.columns.contains(&column))
+            .map(|hit| hit.target)
+    }
+
+    pub fn frame(&mut self, width: u16, height: u16, status: &PomodoroStatus, stats: &StatsData) -> Frame {
+        self.hits.clear();
+        let mut frame = self.tab_frame(width, height, status, stats);
+        if height == 0 {
+            return frame;
+        }
+
+        // The prompt, or else a message, takes over the bottom row
+        let bottom = height - 1;
+        if self.prompt.is_some() || self.message.is_some() {
+            self.hits.retain(|hit| !hit.rows.contains(&bottom));
+        }
+        if let Some(prompt) = &self.prompt {
+            frame.print(0, bottom, &" ".repeat(width as usize), Color::Reset, false);
+            let text = format!("{}{}_", prompt.kind.label(), prompt.input);
+            // Keep the end of long input visible
+            let skip = text.chars().count().saturating_sub(width as usize);
+            frame.print(0, bottom, &text.chars().skip(skip).collect::<String>(), self.theme.text, true);
+        } else if let Some(message) = &self.message {
+            frame.print(0, bottom, &" ".repeat(width as usize), Color::Reset, false);
+            frame.print_centered(bottom, message, self.theme.text, false);
+        }
+
+        if let Some(triage) = &self.triage {
+            let arrow = self.theme.symbols.arrow;
+            // Scroll the list so the selected note stays inside the box
+            let visible = (height as usize).saturating_sub(4).max(1);
+            let skip = (triage.selected + 1).saturating_sub(visible);
+            let lines: Vec<String> = triage
+                .notes
+                .iter()
+                .enumerate()
+                .skip(skip)
+                .take(visible)
+                .map(|(i, note)| {
+                    let marker = if i == triage.selected { arrow } else { " " };
+                    format!("{} {}  {}", marker, note.created_at.format("%H:%M"), note.text)
+                })
+                .collect();
+            draw_box(&mut frame, "Noted during work", &lines, "enter make task  d dismiss  esc later", &self.theme);
+        }
+
+        if self.show_help {
+            draw_box(&mut frame, "Key bindings", &self.help, "Press any key to close", &self.theme);
+        }
+
+        frame
+    }
+
+    /// Lay out the selected tab. Scrolling is clamped here, where the content height is known.
+    fn tab_frame(&mut self, width: u16, height: u16, status: &PomodoroStatus, stats: &StatsData) -> Frame {
+        if self.tab == Tab::Timer {
+            return timer_frame(width, height, status, stats.today.as_ref(), &self.footers, &self.theme, &mut self.hits);
+        }
+
+        let theme = &self.theme;
+        let mut frame = Frame::new(width, height);
+        // Tab bar, timer header, rule and footer need four rows on top of the content
+        if width < MIN_WIDTH || height < MIN_HEIGHT + 4 {
+            draw_compact(&mut frame, status, theme, &mut self.hits);
+            return frame;
+        }
+
+        let mut tab_bar = StyledLine::new();
+        let mut x = 0;
+        for (i, tab) in Tab::ALL.iter().enumerate() {
+            let color = if *tab == self.tab { theme.accent } else { theme.muted };
+            let title = format!(" {} {} ", i + 1, tab.title());
+            let len = title.chars().count() as u16;
+            self.hits.push(Hit { rows: 0..1, columns: x..x + len, target: Target::Tab(*tab) });
+            x += len;
+            tab_bar.push((title, color));
+        }
+        frame.print_styled(0, 0, &tab_bar);
+
+        // Keep the countdown running above the stats
+        let color = theme.state_color(status.state);
+        let header = format!(
+            "{}  {}  {}",
+            state_text(status),
+            time_text(status),
+            progress_bar(status, (width / 3).min(MAX_BAR_WIDTH) as usize, theme)
+        );
+        frame.print(0, 1, &header, color, true);
+        frame.print(0, 2, &theme.symbols.rule.repeat(width as usize), theme.muted, false);
+
+        let body = match self.tab {
+            Tab::Timer => Vec::new(),
+            Tab::Today => today_lines(stats, width, theme),
+            Tab::Daily => no_data(stats_chart::daily_chart_lines(&stats.daily, width, theme), theme),
+            Tab::Summary => summary_lines(stats, theme),
+            Tab::Types => no_data(stats_chart::type_chart_lines(&stats.types, width, theme), theme),
+        };
+
+        let body_height = (height - 4) as usize;
+        self.scroll = self.scroll.min(body.len().saturating_sub(body_height));
+        for (row, line) in body.iter().skip(self.scroll).take(body_height).enumerate() {
+            frame.print_styled(0, 3 + row as u16, line);
+        }
+
+        draw_footer(&mut frame, height - 1, &self.stats_footers, theme.muted, &mut self.hits);
+
+        frame
+    }
+}
+
+/// Draw a bordered box with `lines` in the middle of the frame, clipped if it doesn't fit
+fn draw_box(frame: &mut Frame, title: &str, lines: &[String], footer: &str, theme: &Theme) {
+    let content_width = lines
+        .iter()
+        .map(|line| line.chars().count())
+        .chain([title.chars().count(), footer.chars().count()])
+        .max()
+        .unwrap_or(0);
+    let box_width = (content_width + 4).min(frame.width as usize) as u16;
+    let box_height = (lines.len() + 4).min(frame.height as usize) as u16;
+    let left = (frame.width - box_width) / 2;
+    let top = (frame.height - box_height) / 2;
+    let inner = box_width.saturating_sub(2) as usize;
+
+    let [top_left, top_right, bottom_left, bottom_right] = theme.symbols.box_corners;
+    let (rule, side) = (theme.symbols.rule, theme.symbols.box_side);
+    for row in 0..box_height {
+        let text = if row == 0 {
+            format!("{}{}{}", top_left, rule.repeat(inner), top_right)
+        } else if row == box_height - 1 {
+            format!("{}{}{}", bottom_left, rule.repeat(inner), bottom_right)
+        } else {
+            format!("{}{}{}", side, " ".repeat(inner), side)
+        };
+        frame.print(left, top + row, &text, theme.text, false);
+    }
+
+    let x = left + 2;
+    frame.print_centered_in(left, box_width, top + 1, title, theme.text, true);
+    for (i, line) in lines.iter().enumerate() {
+        let y = top + 2 + i as u16;
+        if y + 2 >= top + box_height {
+            break;
+        }
+        frame.print(x, y, &line.chars().take(inner.saturating_sub(2)).collect::<String>(), theme.text, false);
+    }
+    if box_height >= 3 {
+        frame.print_centered_in(left, box_width, top + box_height - 2, footer, theme.muted, false);
+    }
+}